@@ -0,0 +1,103 @@
+//! Measures how many ticks per second the simulation manages with thousands of entities,
+//! and how the spatial index compares to checking every pair of entities for collisions.
+//!
+//! Ships are made too sturdy to go down and don't hurt each other when they crash,
+//! so the population stays put for the whole run.
+//!
+//! Run with `cargo bench`.
+
+use std::time::{Duration, Instant};
+
+use term_skirmish_rust::collision::find_contacts;
+use term_skirmish_rust::helpers::collided;
+use term_skirmish_rust::spatial::SpatialIndex;
+use term_skirmish_rust::{Arena, Entities, Game, GameConfig, Rules};
+
+/// Ticks to let the waves spread out from the edges before measuring
+const WARM_UP: u64 = 100;
+const TICKS: u64 = 200;
+
+fn main() {
+    for waves in [10, 50, 100, 200] {
+        let mut game = Game::new(GameConfig {
+            teams: 8,
+            wave: 100,
+            seed: Some(42),
+            arena: Arena {
+                width: 400,
+                height: 200,
+            },
+            rules: Rules {
+                // no new waves while measuring
+                reinforcement_odds: u32::MAX,
+                ship_hp: u16::MAX,
+                ram_damage: 0,
+                ..Rules::default()
+            },
+            ..Default::default()
+        })
+        .unwrap();
+
+        for _ in 1..waves {
+            game.reinforce();
+        }
+
+        for _ in 0..WARM_UP {
+            game.tick();
+        }
+
+        let mut entity_ticks = 0;
+        let start = Instant::now();
+
+        for _ in 0..TICKS {
+            game.tick();
+            entity_ticks += game.entities().len();
+        }
+
+        let elapsed = start.elapsed();
+        let entities = game.entities();
+
+        let indexed = time(|| {
+            find_contacts(entities, &SpatialIndex::new(entities));
+        });
+        let pairwise = time(|| {
+            every_pair_contacts(entities);
+        });
+
+        println!(
+            "{:>6} entities on average: {:>8.1} ticks/s, collisions {:>9.3} ms indexed vs {:>9.3} ms pairwise",
+            entity_ticks / TICKS as usize,
+            TICKS as f64 / elapsed.as_secs_f64(),
+            indexed.as_secs_f64() * 1000.0,
+            pairwise.as_secs_f64() * 1000.0,
+        );
+    }
+}
+
+/// Every pair of entities that collided, found the way it was done before the spatial index
+fn every_pair_contacts(entities: &Entities) -> Vec<(usize, usize)> {
+    let mut contacts = Vec::new();
+
+    for (i, entity) in entities.iter().enumerate() {
+        for (j, other) in entities.iter().enumerate().skip(i + 1) {
+            if collided(entity, other) {
+                contacts.push((i, j));
+            }
+        }
+    }
+
+    contacts
+}
+
+/// Average time a single run takes, over enough runs to smooth out the noise
+fn time(mut f: impl FnMut()) -> Duration {
+    const RUNS: u32 = 10;
+
+    let start = Instant::now();
+
+    for _ in 0..RUNS {
+        f();
+    }
+
+    start.elapsed() / RUNS
+}
@@ -0,0 +1,96 @@
+use std::cell::RefCell;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::rc::Rc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde_json::json;
+
+use crate::charset::Charset;
+use crate::game::Game;
+use crate::renderer::Renderer;
+use crate::terminal::{CLEAR, HIDE_CURSOR};
+
+/// Writes terminal output to an [asciicast v2](https://docs.asciinema.org/manual/asciicast/v2/) file
+pub struct Asciicast {
+    writer: BufWriter<File>,
+}
+
+impl Asciicast {
+    pub fn create<P: AsRef<Path>>(path: P, width: usize, height: usize) -> io::Result<Asciicast> {
+        let mut cast = Asciicast {
+            writer: BufWriter::new(File::create(path)?),
+        };
+
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |time| time.as_secs());
+
+        let header = json!({
+            "version": 2,
+            "width": width,
+            "height": height,
+            "timestamp": timestamp,
+        });
+
+        writeln!(cast.writer, "{}", header)?;
+
+        Ok(cast)
+    }
+
+    /// Add output written to the terminal `time` seconds into the recording
+    pub fn write(&mut self, time: f64, output: &str) -> io::Result<()> {
+        writeln!(self.writer, "{}", json!([time, "o", output]))
+    }
+
+    /// Make sure everything recorded so far is in the file
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+/// Capture every frame of the game into an asciicast file, as it would be drawn in a terminal.
+///
+/// Frames are timed by the game's frame rate, so a battle recorded in headless mode
+/// plays back at the same speed as it would have run in the terminal.
+/// The file is flushed when the game ends.
+pub fn record_asciicast<P: AsRef<Path>>(
+    game: &mut Game,
+    path: P,
+    charset: Charset,
+) -> io::Result<()> {
+    let mut renderer = Renderer::new(game.arena(), charset);
+    let (width, height) = renderer.size();
+
+    let mut cast = Asciicast::create(path, width, height)?;
+    let first_frame = renderer.render(game);
+    cast.write(0.0, &format!("{}{}{}", HIDE_CURSOR, CLEAR, first_frame))?;
+
+    let cast = Rc::new(RefCell::new(cast));
+    let end_cast = Rc::clone(&cast);
+
+    game.on_tick(move |game, _| {
+        let time = game.ticks() as f64 / f64::from(game.rules().fps);
+
+        let frame = renderer.render(game);
+
+        // nothing changed on screen
+        if frame.is_empty() {
+            return;
+        }
+
+        cast.borrow_mut()
+            .write(time, &frame)
+            .expect("Couldn't write to the asciicast file")
+    });
+
+    game.on_end(move |_, _| {
+        end_cast
+            .borrow_mut()
+            .flush()
+            .expect("Couldn't write to the asciicast file")
+    });
+
+    Ok(())
+}
@@ -0,0 +1,220 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use rand::prelude::*;
+use rand::rngs::StdRng;
+use serde::{Deserialize, Serialize};
+
+use crate::entities::{Entity, EntityBehavior};
+use crate::helpers::*;
+use crate::powerup::nearby_power_up;
+use crate::rules::Rules;
+use crate::ship::{Ship, Team};
+use crate::world::World;
+
+/// What a ship wants to do this tick
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Intent {
+    /// Where the ship is headed. It takes a step toward it whenever its engine is charged.
+    /// Ships flying in formation never leave the arena, so they can't reach a destination outside it.
+    pub destination: Position,
+    /// Which way to fire, if the gun is loaded. Holding fire still uses up the shot.
+    pub fire: Option<(i16, i16)>,
+}
+
+/// Chance, every tick, that a ship with the classic brain goes after a power-up it sees
+pub const POWER_UP_GREED: f64 = 0.05;
+
+/// Decides where a ship goes and what it shoots at
+pub trait ShipBrain {
+    /// Called once per tick for every ship, after it has moved
+    fn think(&self, ship: &Ship, rng: &mut StdRng, world: &World) -> Intent;
+}
+
+/// The original behavior: wander around, chase the enemy now and then,
+/// grab a power-up when one is close by, and take a shot at the nearest enemy in line
+/// when not feeling too scared
+#[derive(Copy, Clone, Debug, Default)]
+pub struct ClassicBrain;
+
+impl ShipBrain for ClassicBrain {
+    fn think(&self, ship: &Ship, rng: &mut StdRng, world: &World) -> Intent {
+        let destination = if ship.has_reached_destination() {
+            if rng.gen_bool(0.5) {
+                random_position(rng, world.arena)
+            } else {
+                enemy_destination(ship, rng, world)
+            }
+        } else {
+            ship.destination()
+        };
+
+        let destination = match nearby_power_up(ship, world) {
+            Some(power_up) if power_up != destination && rng.gen_bool(POWER_UP_GREED) => power_up,
+            _ => destination,
+        };
+
+        let wuss_out = ship.class().wuss_out(world.rules.wuss_out);
+
+        let fire = if ship.gun_loaded(world.rules) && !rng.gen_bool(wuss_out) {
+            aim(ship, world)
+        } else {
+            None
+        };
+
+        Intent { destination, fire }
+    }
+}
+
+/// Always goes after the enemy, and never holds its fire
+#[derive(Copy, Clone, Debug, Default)]
+pub struct HunterBrain;
+
+impl ShipBrain for HunterBrain {
+    fn think(&self, ship: &Ship, rng: &mut StdRng, world: &World) -> Intent {
+        let target = world.rules.targeting(ship.team()).pick(ship, rng, world);
+
+        let destination = match target {
+            Some(i) => world.entities[i].get_position(),
+            None if ship.has_reached_destination() => random_position(rng, world.arena),
+            None => ship.destination(),
+        };
+
+        let fire = if ship.gun_loaded(world.rules) {
+            aim(ship, world)
+        } else {
+            None
+        };
+
+        Intent { destination, fire }
+    }
+}
+
+fn enemy_destination(ship: &Ship, rng: &mut StdRng, world: &World) -> Position {
+    match world.rules.targeting(ship.team()).pick(ship, rng, world) {
+        Some(i) => world.entities[i].get_position(),
+        None => random_position(rng, world.arena),
+    }
+}
+
+/// The direction to the nearest enemy ship in a straight line from the ship
+/// with no obstacle in the way, if there is one. With friendly fire on,
+/// enemies behind a teammate are left alone too.
+pub fn aim(ship: &Ship, world: &World) -> Option<(i16, i16)> {
+    // the closest enemy found so far, as (steps, entity index, direction)
+    let mut best: Option<(i32, usize, (i16, i16))> = None;
+
+    for (x, y) in DIRECTIONS {
+        let mut pos = ship.get_position();
+
+        for steps in 1.. {
+            // no point looking further than something closer we already found
+            if best.is_some_and(|(closest, _, _)| steps > closest) {
+                break;
+            }
+
+            pos = Position(pos.0 + i32::from(x), pos.1 + i32::from(y));
+
+            if !world.arena.contains(pos) || world.index.is_blocked(pos) {
+                break;
+            }
+
+            let mut enemy = None;
+            let mut teammate = false;
+
+            for &i in world.index.at(pos) {
+                match &world.entities[i] {
+                    Entity::Ship(other) if other.team() == ship.team() => teammate = true,
+                    Entity::Ship(_) => enemy = enemy.or(Some(i)),
+                    _ => (),
+                }
+            }
+
+            if let Some(i) = enemy {
+                if best.is_none_or(|(closest, first, _)| (steps, i) < (closest, first)) {
+                    best = Some((steps, i, (x, y)));
+                }
+
+                break;
+            }
+
+            // the bullet would hit the teammate first
+            if teammate && world.rules.friendly_fire {
+                break;
+            }
+        }
+    }
+
+    best.map(|(_, _, direction)| direction)
+}
+
+/// The brains that come with the game, which can be picked in the rules
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BrainKind {
+    #[default]
+    Classic,
+    Hunter,
+}
+
+impl BrainKind {
+    pub fn build(&self) -> Box<dyn ShipBrain> {
+        match self {
+            BrainKind::Classic => Box::new(ClassicBrain),
+            BrainKind::Hunter => Box::new(HunterBrain),
+        }
+    }
+}
+
+impl FromStr for BrainKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "classic" => Ok(BrainKind::Classic),
+            "hunter" => Ok(BrainKind::Hunter),
+            _ => Err(format!("unknown brain `{}`, expected classic or hunter", s)),
+        }
+    }
+}
+
+impl fmt::Display for BrainKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            BrainKind::Classic => "classic",
+            BrainKind::Hunter => "hunter",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+/// The brain flying the ships of each team. Teams without one of their own get the classic brain.
+#[derive(Default)]
+pub struct Brains {
+    teams: HashMap<Team, Box<dyn ShipBrain>>,
+}
+
+impl Brains {
+    pub fn from_rules(rules: &Rules) -> Brains {
+        Brains {
+            teams: rules
+                .brains
+                .iter()
+                .map(|(&team, kind)| (team, kind.build()))
+                .collect(),
+        }
+    }
+
+    pub fn set(&mut self, team: Team, brain: Box<dyn ShipBrain>) {
+        self.teams.insert(team, brain);
+    }
+
+    pub fn get(&self, team: Team) -> &dyn ShipBrain {
+        match self.teams.get(&team) {
+            Some(brain) => brain.as_ref(),
+            None => &ClassicBrain,
+        }
+    }
+}
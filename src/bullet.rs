@@ -1,39 +1,79 @@
-use rand::prelude::ThreadRng;
+use rand::rngs::StdRng;
 
-use crate::entities::{Entities, Entity, EntityBehavior};
+use crate::entities::{Entity, EntityBehavior};
 use crate::helpers::*;
-use crate::terminal::get_size;
+use crate::rules::Rules;
+use crate::ship::Team;
+use crate::world::World;
 
-#[derive(Clone, PartialEq, Eq, Hash)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Bullet {
     position: Position,
     prev_position: Position,
     direction: (i16, i16),
     active: bool,
     bullet_power: i32,
+    team: Team,
+    damage: u16,
+    move_power: Option<i32>,
+    shooter: Option<u32>,
 }
 
 impl Bullet {
-    pub fn new(pos: Position, direction: (i16, i16)) -> Bullet {
+    pub fn new(pos: Position, direction: (i16, i16), team: Team, damage: u16) -> Bullet {
         Bullet {
             position: pos,
             prev_position: pos,
             direction,
             active: true,
             bullet_power: 1,
+            team,
+            damage,
+            move_power: None,
+            shooter: None,
         }
     }
 
-    fn move_self(&mut self) {
+    /// The team of the ship that fired the bullet
+    pub fn team(&self) -> Team {
+        self.team
+    }
+
+    /// The same bullet, fired by the ship with the given number
+    pub fn with_shooter(mut self, id: u32) -> Bullet {
+        self.shooter = Some(id);
+        self
+    }
+
+    /// The number of the ship that fired the bullet, if it's known
+    pub fn shooter(&self) -> Option<u32> {
+        self.shooter
+    }
+
+    /// The same bullet, waiting the given number of ticks between moves instead of the rule
+    pub fn with_move_power(mut self, move_power: i32) -> Bullet {
+        self.move_power = Some(move_power);
+        self
+    }
+
+    /// Hit points taken off the ship the bullet hits
+    pub fn damage(&self) -> u16 {
+        self.damage
+    }
+
+    pub fn direction(&self) -> (i16, i16) {
+        self.direction
+    }
+
+    fn move_self(&mut self, arena: Arena) {
         self.prev_position = self.get_position();
         let x_pos = self.position.0 + self.direction.0 as i32;
         let y_pos = self.position.1 + self.direction.1 as i32;
 
-        let (max_x, max_y) = get_size();
-        if x_pos < 1 || x_pos > max_x.into() || y_pos < 1 || y_pos > max_y.into() {
-            self.active = false;
-        } else {
+        if arena.contains(Position(x_pos, y_pos)) {
             self.position = Position(x_pos, y_pos);
+        } else {
+            self.active = false;
         }
     }
 }
@@ -55,18 +95,32 @@ impl EntityBehavior for Bullet {
         !self.active
     }
 
-    fn take_turn(mut self, _: &mut ThreadRng, _: &Entities) -> (Bullet, Option<Entity>) {
-        if self.bullet_power == 1 {
-            self.move_self();
+    fn take_turn(mut self, _: &mut StdRng, world: &World) -> (Bullet, Vec<Entity>) {
+        let move_power = self.move_power.unwrap_or(world.rules.bullet_move_power);
+
+        if self.bullet_power >= move_power {
+            self.move_self(world.arena);
             self.bullet_power = 0;
         } else {
-            self.bullet_power = 1;
+            self.bullet_power += 1;
         }
 
-        (self, None)
+        (self, Vec::new())
     }
 
-    fn on_collide(mut self, _: &Entity) -> Bullet {
+    fn on_collide(mut self, others: &[&Entity], rules: &Rules) -> Bullet {
+        // power-ups are only there for ships to pick up,
+        // and without friendly fire the bullet flies right past teammates
+        let passes_through = |e: &&Entity| match e {
+            Entity::PowerUp(_) => true,
+            Entity::Ship(ship) => !rules.friendly_fire && ship.team() == self.team,
+            _ => false,
+        };
+
+        if others.iter().all(passes_through) {
+            return self;
+        }
+
         self.active = false;
         self
     }
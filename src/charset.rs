@@ -0,0 +1,181 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::class::ShipClass;
+use crate::entities::{Entity, EntityBehavior};
+use crate::helpers::Arena;
+use crate::powerup::Effect;
+use crate::ship::Team;
+
+/// The set of characters used to draw entities
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum Charset {
+    /// Plain ANSI-colored characters that every terminal can show
+    Ascii,
+    /// Colored single-width symbols
+    Unicode,
+    /// The original look, where everything is a double-width emoji
+    #[default]
+    Emoji,
+}
+
+/// What an entity looks like on screen
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Glyph<'a> {
+    pub symbol: &'a str,
+    /// SGR parameters for the color of the symbol, if any
+    pub color: Option<&'static str>,
+}
+
+impl Charset {
+    /// Number of terminal columns taken up by a single cell of the arena
+    pub fn cell_width(&self) -> u16 {
+        match self {
+            Charset::Emoji => 2,
+            _ => 1,
+        }
+    }
+
+    /// The arena that fits in a terminal of the given size
+    pub fn arena_for(&self, (columns, rows): (u16, u16)) -> Arena {
+        Arena {
+            width: columns / self.cell_width(),
+            height: rows,
+        }
+    }
+
+    pub fn glyph<'a>(&self, entity: &'a Entity) -> Glyph<'a> {
+        let symbol = match (self, entity) {
+            (Charset::Emoji, _) => entity.avatar(),
+            (Charset::Ascii, Entity::Ship(ship)) => match ship.class() {
+                ShipClass::Scout => ">",
+                ShipClass::Fighter => "@",
+                ShipClass::Tank => "#",
+                ShipClass::Sniper => "!",
+                ShipClass::Carrier => "&",
+            },
+            (Charset::Ascii, Entity::Bullet(_)) => ".",
+            (Charset::Ascii, Entity::Explosion(_)) => "*",
+            (Charset::Ascii, Entity::Obstacle(_)) => "%",
+            (Charset::Ascii, Entity::PowerUp(_)) => "+",
+            (Charset::Unicode, Entity::Ship(ship)) => match ship.class() {
+                ShipClass::Scout => "▸",
+                ShipClass::Fighter => "●",
+                ShipClass::Tank => "■",
+                ShipClass::Sniper => "◆",
+                ShipClass::Carrier => "⬢",
+            },
+            (Charset::Unicode, Entity::Bullet(_)) => "•",
+            (Charset::Unicode, Entity::Explosion(_)) => "✶",
+            (Charset::Unicode, Entity::Obstacle(_)) => "▓",
+            (Charset::Unicode, Entity::PowerUp(_)) => "✚",
+        };
+
+        let color = match (self, entity) {
+            (Charset::Emoji, Entity::Ship(ship)) if ship.class() != ShipClass::Fighter => {
+                Some(if ship.is_damaged() {
+                    dim_team_background(ship.team())
+                } else {
+                    team_background(ship.team())
+                })
+            }
+            (Charset::Emoji, _) => None,
+            (_, Entity::Ship(ship)) if ship.is_damaged() => Some(dim_team_color(ship.team())),
+            (_, Entity::Ship(ship)) => Some(team_color(ship.team())),
+            (_, Entity::Bullet(_)) => Some("1;37"),
+            (_, Entity::Explosion(_)) => Some("1;31"),
+            (_, Entity::Obstacle(_)) => Some("90"),
+            (_, Entity::PowerUp(power_up)) => Some(match power_up.effect() {
+                Effect::RapidFire => "1;93",
+                Effect::SpeedBoost => "1;96",
+                Effect::Shield => "1;97",
+                Effect::SpreadShot => "1;95",
+            }),
+        };
+
+        Glyph { symbol, color }
+    }
+}
+
+fn team_color(team: Team) -> &'static str {
+    match team {
+        Team::BLUE => "34",
+        Team::RED => "31",
+        Team::YELLOW => "33",
+        Team::GREEN => "32",
+        Team::ORANGE => "38;5;208",
+        Team::BROWN => "38;5;94",
+        Team::PURPLE => "35",
+        Team::WHITE => "37",
+    }
+}
+
+/// The team color, dimmed for ships that have taken a hit
+fn dim_team_color(team: Team) -> &'static str {
+    match team {
+        Team::BLUE => "2;34",
+        Team::RED => "2;31",
+        Team::YELLOW => "2;33",
+        Team::GREEN => "2;32",
+        Team::ORANGE => "2;38;5;208",
+        Team::BROWN => "2;38;5;94",
+        Team::PURPLE => "2;35",
+        Team::WHITE => "2;37",
+    }
+}
+
+/// The team color behind emoji that don't come in every team color
+fn team_background(team: Team) -> &'static str {
+    match team {
+        Team::BLUE => "44",
+        Team::RED => "41",
+        Team::YELLOW => "43",
+        Team::GREEN => "42",
+        Team::ORANGE => "48;5;208",
+        Team::BROWN => "48;5;94",
+        Team::PURPLE => "45",
+        Team::WHITE => "47",
+    }
+}
+
+/// A darker team background, for ships that have taken a hit
+fn dim_team_background(team: Team) -> &'static str {
+    match team {
+        Team::BLUE => "48;5;17",
+        Team::RED => "48;5;52",
+        Team::YELLOW => "48;5;100",
+        Team::GREEN => "48;5;22",
+        Team::ORANGE => "48;5;130",
+        Team::BROWN => "48;5;58",
+        Team::PURPLE => "48;5;53",
+        Team::WHITE => "48;5;245",
+    }
+}
+
+impl FromStr for Charset {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ascii" => Ok(Charset::Ascii),
+            "unicode" => Ok(Charset::Unicode),
+            "emoji" => Ok(Charset::Emoji),
+            _ => Err(format!(
+                "unknown charset `{}`, expected ascii, unicode or emoji",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Charset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Charset::Ascii => "ascii",
+            Charset::Unicode => "unicode",
+            Charset::Emoji => "emoji",
+        };
+
+        write!(f, "{}", name)
+    }
+}
@@ -0,0 +1,122 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+/// What kind of ship it is, which sets how fast it flies and shoots and how much it can take.
+///
+/// Every class is measured against the rules, so a fighter plays exactly by them.
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum ShipClass {
+    /// Twice as fast, half the hit points, and quick to lose its nerve
+    Scout,
+    /// Plays by the rules
+    #[default]
+    Fighter,
+    /// Half as fast and slower to reload, but three times the hit points and hard to scare
+    Tank,
+    /// Takes twice as long to reload, but its bullets fly twice as fast and it rarely holds fire
+    Sniper,
+    /// Half as fast, with four times the hit points, and launches scouts instead of shooting
+    Carrier,
+}
+
+impl ShipClass {
+    pub fn all() -> [ShipClass; 5] {
+        [
+            ShipClass::Scout,
+            ShipClass::Fighter,
+            ShipClass::Tank,
+            ShipClass::Sniper,
+            ShipClass::Carrier,
+        ]
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            ShipClass::Scout => "scout",
+            ShipClass::Fighter => "fighter",
+            ShipClass::Tank => "tank",
+            ShipClass::Sniper => "sniper",
+            ShipClass::Carrier => "carrier",
+        }
+    }
+
+    /// Ticks the ship waits between moves, given the rule for fighters
+    pub fn move_power(&self, move_power: i32) -> i32 {
+        match self {
+            ShipClass::Scout => move_power / 2,
+            ShipClass::Fighter | ShipClass::Sniper => move_power,
+            ShipClass::Tank | ShipClass::Carrier => move_power * 2,
+        }
+    }
+
+    /// Ticks the ship waits between shots, given the rule for fighters
+    pub fn bullet_power(&self, bullet_power: i32) -> i32 {
+        match self {
+            ShipClass::Scout | ShipClass::Fighter => bullet_power,
+            ShipClass::Tank => bullet_power * 3 / 2,
+            ShipClass::Sniper => bullet_power * 2,
+            ShipClass::Carrier => bullet_power * 4,
+        }
+    }
+
+    /// Ticks the ship's bullets wait between moves, given the rule for fighters
+    pub fn bullet_move_power(&self, bullet_move_power: i32) -> i32 {
+        match self {
+            ShipClass::Sniper => bullet_move_power / 2,
+            _ => bullet_move_power,
+        }
+    }
+
+    pub fn hp(&self, ship_hp: u16) -> u16 {
+        match self {
+            ShipClass::Scout => (ship_hp / 2).max(1),
+            ShipClass::Fighter | ShipClass::Sniper => ship_hp,
+            ShipClass::Tank => ship_hp.saturating_mul(3),
+            ShipClass::Carrier => ship_hp.saturating_mul(4),
+        }
+    }
+
+    /// Chance of holding fire with a loaded gun, given the rule for fighters
+    pub fn wuss_out(&self, wuss_out: f64) -> f64 {
+        match self {
+            ShipClass::Scout => (wuss_out * 1.5).min(1.0),
+            ShipClass::Fighter | ShipClass::Carrier => wuss_out,
+            ShipClass::Tank | ShipClass::Sniper => wuss_out / 2.0,
+        }
+    }
+
+    /// The kind of ship launched in place of a bullet, if any
+    pub fn launches(&self) -> Option<ShipClass> {
+        match self {
+            ShipClass::Carrier => Some(ShipClass::Scout),
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for ShipClass {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ShipClass::all()
+            .into_iter()
+            .find(|class| class.name() == s)
+            .ok_or_else(|| {
+                format!(
+                    "unknown ship class `{}`, expected scout, fighter, tank, sniper or carrier",
+                    s
+                )
+            })
+    }
+}
+
+impl fmt::Display for ShipClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
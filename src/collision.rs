@@ -0,0 +1,56 @@
+use crate::entities::{Entities, Entity, EntityBehavior};
+use crate::helpers::collided;
+use crate::rules::Rules;
+use crate::spatial::SpatialIndex;
+
+/// Every pair of entities that collided, as indices into `entities`, with the lower index first
+pub fn find_contacts(entities: &Entities, index: &SpatialIndex) -> Vec<(usize, usize)> {
+    let mut contacts = Vec::new();
+
+    for (i, entity) in entities.iter().enumerate() {
+        for j in index.collision_candidates(entity) {
+            if j > i && collided(entity, &entities[j]) {
+                contacts.push((i, j));
+            }
+        }
+    }
+
+    contacts
+}
+
+/// Let every entity react to everything it collided with.
+///
+/// Each entity sees the others as they were before any collision was applied,
+/// so the outcome doesn't depend on the order of `entities`.
+pub fn resolve_collisions(entities: &Entities, rules: &Rules) -> Entities {
+    let index = SpatialIndex::new(entities);
+    let contacts = find_contacts(entities, &index);
+
+    resolve_contacts(entities, &contacts, rules)
+}
+
+/// Like [`resolve_collisions`], for contacts that were already found with [`find_contacts`]
+pub fn resolve_contacts(
+    entities: &Entities,
+    contacts: &[(usize, usize)],
+    rules: &Rules,
+) -> Entities {
+    let mut others: Vec<Vec<&Entity>> = vec![Vec::new(); entities.len()];
+
+    for &(i, j) in contacts {
+        others[i].push(&entities[j]);
+        others[j].push(&entities[i]);
+    }
+
+    entities
+        .iter()
+        .zip(others)
+        .map(|(entity, others)| {
+            if others.is_empty() {
+                entity.clone()
+            } else {
+                entity.clone().on_collide(&others, rules)
+            }
+        })
+        .collect()
+}
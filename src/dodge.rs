@@ -0,0 +1,68 @@
+use rand::prelude::*;
+use rand::rngs::StdRng;
+
+use crate::entities::Entity;
+use crate::helpers::*;
+use crate::ship::Team;
+use crate::world::World;
+
+/// The direction of a bullet that will reach the position within `look_ahead` of its steps,
+/// if there is one. Bullets behind an obstacle can't get through, and with friendly fire off
+/// neither can the bullets of the team itself.
+pub fn incoming_bullet(pos: Position, team: Team, world: &World) -> Option<(i16, i16)> {
+    let look_ahead = i32::from(world.rules.dodge_look_ahead);
+
+    DIRECTIONS.into_iter().find(|&(x, y)| {
+        (1..=look_ahead)
+            .map(|steps| Position(pos.0 - i32::from(x) * steps, pos.1 - i32::from(y) * steps))
+            .take_while(|&from| !world.index.is_blocked(from))
+            .any(|from| {
+                world
+                    .index
+                    .at(from)
+                    .iter()
+                    .any(|&i| match &world.entities[i] {
+                        Entity::Bullet(bullet) => {
+                            bullet.direction() == (x, y)
+                                && (world.rules.friendly_fire || bullet.team() != team)
+                        }
+                        _ => false,
+                    })
+            })
+    })
+}
+
+/// A step out of the way of a bullet flying in the given direction,
+/// onto a spot that no other bullet is headed for
+pub fn sidestep(
+    pos: Position,
+    team: Team,
+    bullet_direction: (i16, i16),
+    rng: &mut StdRng,
+    world: &World,
+) -> Option<(i16, i16)> {
+    let (x, y) = bullet_direction;
+
+    // at right angles to the bullet, or else diagonally ahead of it
+    let perpendicular = [(-y, x), (y, -x)];
+    let diagonal = [(x - y, y + x), (x + y, y - x)].map(|(x, y)| (x.signum(), y.signum()));
+
+    for steps in [perpendicular, diagonal] {
+        let safe: Vec<(i16, i16)> = steps
+            .into_iter()
+            .filter(|&(x, y)| {
+                let to = Position(pos.0 + i32::from(x), pos.1 + i32::from(y));
+
+                world.arena.contains(to)
+                    && !world.index.is_blocked(to)
+                    && incoming_bullet(to, team, world).is_none()
+            })
+            .collect();
+
+        if let Some(&step) = safe.choose(rng) {
+            return Some(step);
+        }
+    }
+
+    None
+}
@@ -0,0 +1,232 @@
+use std::sync::atomic::{AtomicBool, Ordering::Relaxed};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use signal_hook::consts::SIGWINCH;
+
+use crate::charset::Charset;
+use crate::game::Game;
+use crate::helpers::*;
+use crate::renderer::Renderer;
+use crate::replay::Replay;
+use crate::terminal::*;
+
+/// Run the game in the terminal until the user hits Ctrl-C or the match is over,
+/// then show how each team fared
+pub fn run_game(mut game: Game, charset: Charset) -> Game {
+    hide_cursor();
+    clear();
+
+    let over = interrupt_flag();
+    let resized = resize_flag();
+    let mut renderer = Renderer::new(game.arena(), charset);
+
+    while !over.load(Relaxed) {
+        if game.win_condition().is_some() && game.is_over() {
+            break;
+        }
+
+        if resized.swap(false, Relaxed) {
+            game.resize(charset.arena_for(get_size()));
+
+            // start over from a blank screen
+            clear();
+            renderer = Renderer::new(game.arena(), charset);
+        }
+
+        game.tick();
+
+        write_frame(&renderer.render(&game));
+
+        let fps = game.rules().fps;
+        thread::sleep(Duration::from_millis(1000 / u64::from(fps)));
+    }
+
+    game.end();
+
+    after_game(announcement(&game).as_deref());
+    print!("{}", game.stats());
+
+    game
+}
+
+/// Run the simulation without a terminal, as fast as possible,
+/// until `max_ticks` have passed or the match is over
+pub fn run_headless(mut game: Game, max_ticks: Option<u64>) -> Game {
+    let over = interrupt_flag();
+
+    while !over.load(Relaxed) && !game.is_over() {
+        if max_ticks.is_some_and(|max_ticks| game.ticks() >= max_ticks) {
+            break;
+        }
+
+        game.tick();
+    }
+
+    game.end();
+
+    print_summary(&game);
+
+    game
+}
+
+/// Slowest a replay can be played back, relative to the recorded frame rate
+pub const MIN_SPEED: f64 = 1.0 / 16.0;
+
+/// Fastest a replay can be played back, relative to the recorded frame rate
+pub const MAX_SPEED: f64 = 16.0;
+
+/// Play back a recorded game in the terminal.
+///
+/// Space pauses, the left and right arrows step through single ticks,
+/// `[` and `]` seek ten seconds, `+` and `-` change the speed,
+/// `0` goes back to the start and `q` quits.
+/// The speed is kept between `MIN_SPEED` and `MAX_SPEED`.
+pub fn play_replay(replay: Replay, charset: Charset, speed: f64) {
+    let mut speed = if speed.is_nan() {
+        1.0
+    } else {
+        speed.clamp(MIN_SPEED, MAX_SPEED)
+    };
+
+    // without a terminal to read from, keys can still be piped in
+    let _raw_mode = RawMode::enable().ok();
+    let keys = read_keys();
+
+    hide_cursor();
+    clear();
+
+    let over = interrupt_flag();
+    let mut renderer = Renderer::new(replay.header.config.arena, charset);
+
+    let fps = replay.header.config.rules.fps;
+    let seek_distance = 10 * fps as usize;
+    let last = replay.frames.len() - 1;
+    let mut current = 0;
+    let mut paused = false;
+
+    while !over.load(Relaxed) {
+        for key in keys.try_iter() {
+            match key {
+                Key::Char(' ') => paused = !paused,
+                Key::Right | Key::Char('l') => {
+                    paused = true;
+                    current = (current + 1).min(last);
+                }
+                Key::Left | Key::Char('h') => {
+                    paused = true;
+                    current = current.saturating_sub(1);
+                }
+                Key::Char(']') => current = (current + seek_distance).min(last),
+                Key::Char('[') => current = current.saturating_sub(seek_distance),
+                Key::Char('+') | Key::Char('=') => speed = (speed * 2.0).min(MAX_SPEED),
+                Key::Char('-') => speed = (speed / 2.0).max(MIN_SPEED),
+                Key::Char('0') => current = 0,
+                Key::Char('q') => over.store(true, Relaxed),
+                _ => (),
+            }
+        }
+
+        let frame = &replay.frames[current];
+        let status = format!(
+            "{}     [{}] tick {}/{} at {}x",
+            frame.status,
+            if paused { "paused" } else { "playing" },
+            frame.tick,
+            replay.frames[last].tick,
+            speed,
+        );
+
+        write_frame(&renderer.render_frame(&frame.entities(), &status));
+
+        if !paused && current < last {
+            current += 1;
+        }
+
+        thread::sleep(Duration::from_secs_f64(1.0 / (f64::from(fps) * speed)));
+    }
+
+    after_game(None)
+}
+
+fn interrupt_flag() -> Arc<AtomicBool> {
+    let over = Arc::new(AtomicBool::new(false));
+    let flag = Arc::clone(&over);
+
+    ctrlc::set_handler(move || flag.store(true, Relaxed)).expect("Couldn't set SIGINT handler");
+
+    over
+}
+
+fn resize_flag() -> Arc<AtomicBool> {
+    let resized = Arc::new(AtomicBool::new(false));
+
+    signal_hook::flag::register(SIGWINCH, Arc::clone(&resized))
+        .expect("Couldn't set SIGWINCH handler");
+
+    resized
+}
+
+/// Who won the match, if the game is being played as one
+fn announcement(game: &Game) -> Option<String> {
+    game.win_condition()?;
+
+    if !game.is_over() {
+        return Some("The match was stopped before anyone won.".into());
+    }
+
+    match game.winner() {
+        Some(winner) => Some(format!("{} wins the match! 🏆", winner.name())),
+        None => Some("The match is a draw.".into()),
+    }
+}
+
+fn after_game(announcement: Option<&str>) {
+    clear();
+    show_cursor();
+
+    move_cursor(Position(1, 1));
+    draw(" 🔵  🔸 🔸 🔥");
+
+    let mut line = 3;
+
+    if let Some(announcement) = announcement {
+        move_cursor(Position(0, line));
+        draw(announcement);
+        line += 2;
+    }
+
+    move_cursor(Position(0, line));
+
+    draw("See you again soon! 🦀");
+
+    move_cursor(Position(0, line + 2));
+
+    render();
+    // os.Exit(0)
+}
+
+fn print_summary(game: &Game) {
+    println!("ticks: {}", game.ticks());
+    println!("{}", game.get_status());
+
+    let ships = get_ships_from_entities(game.entities());
+
+    for team in get_teams_from_entities(game.entities()) {
+        let count = ships.iter().filter(|ship| ship.team() == team).count();
+        println!("{}: {} ships remaining", team.name(), count);
+    }
+
+    if game.win_condition().is_some() && !game.is_over() {
+        println!("match stopped before it was over");
+    } else if game.has_started() || game.win_condition().is_some() {
+        match game.winner() {
+            Some(winner) => println!("winner: {}", winner.name()),
+            None => println!("no winner"),
+        }
+    }
+
+    println!();
+    print!("{}", game.stats());
+}
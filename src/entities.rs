@@ -1,24 +1,42 @@
-use rand::prelude::ThreadRng;
+use rand::rngs::StdRng;
 
 use crate::bullet::Bullet;
+use crate::class::ShipClass;
 use crate::explosion::Explosion;
-use crate::helpers::Position;
+use crate::helpers::{Arena, Position};
+use crate::obstacle::Obstacle;
+use crate::powerup::PowerUp;
+use crate::rules::Rules;
 use crate::ship::{Ship, Team};
+use crate::world::World;
 
-#[derive(Clone, PartialEq, Eq, Hash)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum Entity {
     Ship(Ship),
     Bullet(Bullet),
     Explosion(Explosion),
+    Obstacle(Obstacle),
+    PowerUp(PowerUp),
 }
 
 impl Entity {
-    pub fn ship(t: Team) -> Entity {
-        Entity::Ship(Ship::new(t))
+    pub fn ship(
+        t: Team,
+        id: u32,
+        class: ShipClass,
+        rng: &mut StdRng,
+        arena: Arena,
+        rules: &Rules,
+    ) -> Entity {
+        Entity::Ship(Ship::new(t, id, class, rng, arena, rules))
     }
 
-    pub fn explosion(pos: Position) -> Entity {
-        Entity::Explosion(Explosion::new(pos))
+    pub fn explosion(pos: Position, rules: &Rules) -> Entity {
+        Entity::Explosion(Explosion::new(pos, rules.explosion_health))
+    }
+
+    pub fn obstacle(pos: Position) -> Entity {
+        Entity::Obstacle(Obstacle::new(pos))
     }
 }
 
@@ -27,10 +45,14 @@ pub trait EntityBehavior {
     fn get_prev_position(&self) -> Position;
     fn should_remove(&self) -> bool;
     fn avatar(&self) -> &str;
-    fn take_turn(self, rng: &mut ThreadRng, entities: &Entities) -> (Self, Option<Entity>)
+    /// Move, and whatever else the entity does in a tick, along with any entities it brings
+    /// into the game, like the bullets a ship fires
+    fn take_turn(self, rng: &mut StdRng, world: &World) -> (Self, Vec<Entity>)
     where
         Self: Sized;
-    fn on_collide(self, other: &Entity) -> Self;
+    /// React to everything this entity collided with during the tick, all at once,
+    /// so that the outcome doesn't depend on the order of the contacts
+    fn on_collide(self, others: &[&Entity], rules: &Rules) -> Self;
     fn on_remove_explode(&self) -> bool;
 }
 
@@ -38,7 +60,7 @@ pub trait EntityBehavior {
 // it doesn't support customizing the implementation methods
 macro_rules! delegate {
     ($entity:expr, $name:ident) => {
-       delegate!($entity; Ship, Bullet, Explosion; $name)
+       delegate!($entity; Ship, Bullet, Explosion, Obstacle, PowerUp; $name)
     };
     ($entity:expr; $($member:ident),+; $name:ident) => {
         match $entity {
@@ -64,28 +86,38 @@ impl EntityBehavior for Entity {
         delegate!(self, avatar)
     }
 
-    fn take_turn(self, rng: &mut ThreadRng, entities: &Entities) -> (Self, Option<Entity>) {
+    fn take_turn(self, rng: &mut StdRng, world: &World) -> (Self, Vec<Entity>) {
         match self {
             Self::Ship(e) => {
-                let (e, other) = e.take_turn(rng, entities);
+                let (e, other) = e.take_turn(rng, world);
                 (Self::Ship(e), other)
             }
             Self::Bullet(e) => {
-                let (e, other) = e.take_turn(rng, entities);
+                let (e, other) = e.take_turn(rng, world);
                 (Self::Bullet(e), other)
             }
             Self::Explosion(e) => {
-                let (e, other) = e.take_turn(rng, entities);
+                let (e, other) = e.take_turn(rng, world);
                 (Self::Explosion(e), other)
             }
+            Self::Obstacle(e) => {
+                let (e, other) = e.take_turn(rng, world);
+                (Self::Obstacle(e), other)
+            }
+            Self::PowerUp(e) => {
+                let (e, other) = e.take_turn(rng, world);
+                (Self::PowerUp(e), other)
+            }
         }
     }
 
-    fn on_collide(self, other: &Entity) -> Self {
+    fn on_collide(self, others: &[&Entity], rules: &Rules) -> Self {
         match self {
-            Self::Ship(e) => Self::Ship(e.on_collide(other)),
-            Self::Bullet(e) => Self::Bullet(e.on_collide(other)),
-            Self::Explosion(e) => Self::Explosion(e.on_collide(other)),
+            Self::Ship(e) => Self::Ship(e.on_collide(others, rules)),
+            Self::Bullet(e) => Self::Bullet(e.on_collide(others, rules)),
+            Self::Explosion(e) => Self::Explosion(e.on_collide(others, rules)),
+            Self::Obstacle(e) => Self::Obstacle(e.on_collide(others, rules)),
+            Self::PowerUp(e) => Self::PowerUp(e.on_collide(others, rules)),
         }
     }
 
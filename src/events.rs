@@ -0,0 +1,55 @@
+use std::cell::RefCell;
+use std::io::{self, Write};
+use std::rc::Rc;
+
+use serde::Serialize;
+
+use crate::game::Game;
+use crate::report::{Event, TickReport};
+
+#[derive(Serialize)]
+struct Line<'a> {
+    tick: u64,
+    #[serde(flatten)]
+    event: &'a Event,
+}
+
+/// Write every event of the game to `writer` as it happens, one JSON object per line,
+/// ending with the game over event.
+///
+/// The writer is flushed after every tick, so the events can be followed live.
+pub fn stream_events<W: Write + 'static>(game: &mut Game, writer: W) {
+    let writer = Rc::new(RefCell::new(writer));
+    let end_writer = Rc::clone(&writer);
+
+    game.on_tick(move |_, report| {
+        write_events(&mut *writer.borrow_mut(), report).expect("Couldn't write events")
+    });
+
+    game.on_end(move |_, report| {
+        write_events(&mut *end_writer.borrow_mut(), report).expect("Couldn't write events")
+    });
+}
+
+fn write_events<W: Write>(writer: &mut W, report: &TickReport) -> io::Result<()> {
+    for event in &report.events {
+        let line = Line {
+            tick: report.tick,
+            event,
+        };
+
+        serde_json::to_writer(&mut *writer, &line)?;
+        writer.write_all(b"\n")?;
+    }
+
+    writer.flush()
+}
+
+/// Where to send events, given a path or `-` for stderr
+pub fn event_writer(path: &str) -> io::Result<Box<dyn Write>> {
+    if path == "-" {
+        return Ok(Box::new(io::stderr()));
+    }
+
+    Ok(Box::new(io::BufWriter::new(std::fs::File::create(path)?)))
+}
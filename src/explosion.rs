@@ -1,21 +1,27 @@
-use rand::prelude::ThreadRng;
+use rand::rngs::StdRng;
 
-use crate::entities::{Entities, Entity, EntityBehavior};
+use crate::entities::{Entity, EntityBehavior};
 use crate::helpers::*;
+use crate::rules::Rules;
+use crate::world::World;
 
-#[derive(Clone, PartialEq, Eq, Hash)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Explosion {
     position: Position,
     health: u16,
 }
 
 impl Explosion {
-    pub fn new(pos: Position) -> Explosion {
+    pub fn new(pos: Position, health: u16) -> Explosion {
         Explosion {
             position: pos,
-            health: 10,
+            health,
         }
     }
+
+    pub fn health(&self) -> u16 {
+        self.health
+    }
 }
 
 impl EntityBehavior for Explosion {
@@ -35,24 +41,33 @@ impl EntityBehavior for Explosion {
         "💥"
     }
 
-    fn take_turn(mut self, _: &mut ThreadRng, _: &Entities) -> (Explosion, Option<Entity>) {
+    fn take_turn(mut self, _: &mut StdRng, _: &World) -> (Explosion, Vec<Entity>) {
         if self.health > 0 {
             self.health -= 1;
         }
 
-        (self, None)
+        (self, Vec::new())
     }
 
-    fn on_collide(mut self, other_entity: &Entity) -> Explosion {
-        match other_entity {
-            Entity::Explosion(other_explosion) => {
-                if self.health > other_explosion.health {
-                    self.health += 100;
-                } else {
-                    self.health = 0;
-                }
-            }
-            _ => self.health += 100,
+    fn on_collide(mut self, others: &[&Entity], rules: &Rules) -> Explosion {
+        // rock and power-ups don't feed the flames
+        if others
+            .iter()
+            .all(|e| matches!(e, Entity::Obstacle(_) | Entity::PowerUp(_)))
+        {
+            return self;
+        }
+
+        // a bigger (or equally big) explosion swallows this one
+        let swallowed = others.iter().any(|e| match e {
+            Entity::Explosion(other_explosion) => other_explosion.health >= self.health,
+            _ => false,
+        });
+
+        if swallowed {
+            self.health = 0;
+        } else {
+            self.health = self.health.saturating_add(rules.explosion_boost);
         }
 
         self
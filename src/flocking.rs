@@ -0,0 +1,257 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::entities::{Entity, EntityBehavior};
+use crate::helpers::*;
+use crate::ship::Ship;
+use crate::world::World;
+
+/// Teammates within this many steps count as neighbors when flocking
+pub const FLOCK_RADIUS: i32 = 8;
+
+/// Steps between ships flying in formation
+pub const SPACING: i32 = 2;
+
+/// How the ships of a team fly together
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Formation {
+    /// Every ship for itself, the way it has always been
+    #[default]
+    Loose,
+    /// Side by side with the team's oldest ship, across the way it is headed
+    Line,
+    /// A V behind the team's oldest ship
+    Wedge,
+    /// A flock that keeps together and heads the same way,
+    /// while each ship still goes after its own destination
+    Swarm,
+}
+
+/// A 2D vector for adding up steering forces
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+struct Force(f64, f64);
+
+impl Force {
+    fn between(from: Position, to: Position) -> Force {
+        Force(f64::from(to.0 - from.0), f64::from(to.1 - from.1))
+    }
+
+    fn length(&self) -> f64 {
+        self.0.hypot(self.1)
+    }
+
+    /// The same force with a length of `weight`, or nothing if it has no direction
+    fn scaled(&self, weight: f64) -> Force {
+        let length = self.length();
+
+        if length == 0.0 {
+            return Force::default();
+        }
+
+        Force(self.0 / length * weight, self.1 / length * weight)
+    }
+
+    fn add(&mut self, other: Force) {
+        self.0 += other.0;
+        self.1 += other.1;
+    }
+
+    /// The one of the eight steps that comes closest to following the force
+    fn step(&self) -> (i16, i16) {
+        let length = self.length();
+
+        // anything within 22.5° of an axis counts as going straight along it
+        let threshold = length * 0.38;
+        let component = |c: f64| {
+            if c.abs() < threshold || length == 0.0 {
+                0
+            } else {
+                c.signum() as i16
+            }
+        };
+
+        (component(self.0), component(self.1))
+    }
+}
+
+/// The step a ship flying in the given formation takes this time it moves
+pub fn flock(ship: &Ship, formation: Formation, world: &World) -> (i16, i16) {
+    let position = ship.get_position();
+    let teammates = teammates(ship, world);
+
+    let goal = match formation {
+        Formation::Line | Formation::Wedge => slot(ship, formation, &teammates, world),
+        _ => ship.destination(),
+    };
+
+    // one step away, so take it rather than let the neighbors push the ship back and forth
+    if distance(position, goal) == 1 {
+        return direction(position, goal);
+    }
+
+    let mut force = Force::between(position, goal).scaled(1.0);
+
+    let neighbors: Vec<&Ship> = teammates
+        .iter()
+        .copied()
+        .filter(|other| distance(position, other.get_position()) <= FLOCK_RADIUS)
+        .collect();
+
+    // separation, so teammates don't pile up on the same spot
+    for other in neighbors
+        .iter()
+        .filter(|other| distance(position, other.get_position()) <= 1)
+    {
+        let away = match Force::between(other.get_position(), position) {
+            // on top of each other, so the younger ship moves aside
+            Force(x, y) if x == 0.0 && y == 0.0 && ship.id() > other.id() => {
+                let Force(x, y) = Force::between(position, goal);
+                Force(-y, x)
+            }
+            away => away,
+        };
+
+        force.add(away.scaled(1.5));
+    }
+
+    if formation == Formation::Swarm && !neighbors.is_empty() {
+        let count = neighbors.len() as f64;
+
+        // cohesion, toward the middle of the flock
+        let (x, y) = neighbors.iter().fold((0, 0), |(x, y), other| {
+            let Position(ox, oy) = other.get_position();
+            (x + ox, y + oy)
+        });
+        let center = Force(
+            f64::from(x) / count - f64::from(position.0),
+            f64::from(y) / count - f64::from(position.1),
+        );
+        force.add(center.scaled(0.9));
+
+        // alignment, the way the flock is going
+        let mut heading = Force::default();
+        for other in &neighbors {
+            heading.add(Force::between(
+                other.get_prev_position(),
+                other.get_position(),
+            ));
+        }
+        force.add(heading.scaled(0.4));
+    }
+
+    force.step()
+}
+
+/// The other ships on the same team, oldest first
+fn teammates<'a>(ship: &Ship, world: &World<'a>) -> Vec<&'a Ship> {
+    let mut teammates: Vec<&Ship> = world
+        .index
+        .ships_of(ship.team())
+        .iter()
+        .filter_map(|&i| match &world.entities[i] {
+            Entity::Ship(other) if other.id() != ship.id() => Some(other),
+            _ => None,
+        })
+        .collect();
+
+    teammates.sort_unstable_by_key(|other| other.id());
+
+    teammates
+}
+
+/// Where the ship belongs in the formation. The oldest ship of the team leads the way
+/// toward its own destination, and everyone else lines up around it.
+fn slot(ship: &Ship, formation: Formation, teammates: &[&Ship], world: &World) -> Position {
+    match teammates.first() {
+        Some(leader) if leader.id() < ship.id() => {
+            let rank = teammates
+                .iter()
+                .filter(|other| other.id() < ship.id())
+                .count() as i32;
+
+            slot_behind(leader, rank, formation, world)
+        }
+        _ => ship.destination(),
+    }
+}
+
+/// Where the ship of the given rank belongs, counting the leader as 0
+fn slot_behind(leader: &Ship, rank: i32, formation: Formation, world: &World) -> Position {
+    let (x, y) = direction(leader.get_position(), leader.destination());
+    let (x, y) = if (x, y) == (0, 0) {
+        (1, 0)
+    } else {
+        (i32::from(x), i32::from(y))
+    };
+
+    // every other ship goes on the left or the right
+    let side = if rank % 2 == 1 { 1 } else { -1 };
+    let out = (rank + 1) / 2 * SPACING;
+    let back = match formation {
+        Formation::Wedge => out,
+        _ => 0,
+    };
+
+    let Position(lx, ly) = leader.get_position();
+
+    world.arena.clamp(Position(
+        lx - y * side * out - x * back,
+        ly + x * side * out - y * back,
+    ))
+}
+
+/// Whether the ship leads a formation that some of its ships are lagging behind,
+/// so that it should slow down to let them catch up
+pub fn waiting_for_formation(ship: &Ship, formation: Formation, world: &World) -> bool {
+    if !matches!(formation, Formation::Line | Formation::Wedge) {
+        return false;
+    }
+
+    let teammates = teammates(ship, world);
+
+    if teammates
+        .first()
+        .is_some_and(|first| first.id() < ship.id())
+    {
+        return false;
+    }
+
+    teammates.iter().zip(1..).any(|(follower, rank)| {
+        let slot = slot_behind(ship, rank, formation, world);
+
+        distance(follower.get_position(), slot) > 2 * SPACING
+    })
+}
+
+impl FromStr for Formation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "loose" => Ok(Formation::Loose),
+            "line" => Ok(Formation::Line),
+            "wedge" => Ok(Formation::Wedge),
+            "swarm" => Ok(Formation::Swarm),
+            _ => Err(format!(
+                "unknown formation `{}`, expected loose, line, wedge or swarm",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Formation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Formation::Loose => "loose",
+            Formation::Line => "line",
+            Formation::Wedge => "wedge",
+            Formation::Swarm => "swarm",
+        };
+
+        write!(f, "{}", name)
+    }
+}
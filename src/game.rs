@@ -1,196 +1,646 @@
-use std::sync::atomic::{AtomicBool, Ordering::Relaxed};
-use std::sync::Arc;
-use std::thread;
-use std::time::Duration;
+use std::cmp::Reverse;
+use std::mem;
 
-use rand::{prelude::*, thread_rng};
+use rand::prelude::*;
+use rand::rngs::StdRng;
+use serde::{Deserialize, Serialize};
 
+use crate::brain::{Brains, ShipBrain};
+use crate::class::ShipClass;
+use crate::collision::{find_contacts, resolve_contacts};
 use crate::entities::{Entities, Entity, EntityBehavior};
 use crate::helpers::*;
+use crate::obstacle::{asteroids, Map};
+use crate::powerup::PowerUp;
+use crate::report::{Event, Participant, TickReport};
+use crate::rules::{ConfigError, Rules};
 use crate::ship::Team;
-use crate::terminal::*;
+use crate::spatial::SpatialIndex;
+use crate::stats::Stats;
+use crate::victory::WinCondition;
+use crate::world::World;
+
+/// Everything needed to set up a new game
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GameConfig {
+    /// Number of teams (1-8)
+    pub teams: u16,
+    /// Maximum number of ships in each reinforcement wave (1-100)
+    pub wave: u16,
+    /// Seed for the random number generator, to replay the same battle
+    pub seed: Option<u64>,
+    pub arena: Arena,
+    pub rules: Rules,
+    /// How the match is won. Without one, the battle goes on for as long as you let it.
+    #[serde(default)]
+    pub win_condition: Option<WinCondition>,
+    /// Obstacles to lay out before the first wave comes in
+    #[serde(default)]
+    pub map: Map,
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        GameConfig {
+            teams: 2,
+            wave: 8,
+            seed: None,
+            arena: Arena {
+                width: 80,
+                height: 24,
+            },
+            rules: Rules::default(),
+            win_condition: None,
+            map: Map::default(),
+        }
+    }
+}
+
+type Callback = Box<dyn FnMut(&Game, &TickReport)>;
+type EndCallback = Box<dyn FnOnce(&Game, &TickReport)>;
 
 pub struct Game {
-    over: Arc<AtomicBool>,
     num_teams: u16,
     max_ships_per_wave: u16,
     entities: Entities,
     new_entities: Entities,
-    ship_count: u16,
-    rng: ThreadRng,
+    ship_count: u32,
+    waves: u32,
+    seed: u64,
+    rng: StdRng,
+    arena: Arena,
+    rules: Rules,
+    brains: Brains,
+    win_condition: Option<WinCondition>,
+    map: Map,
+    battle_started: bool,
+    ticks: u64,
+    callbacks: Vec<Callback>,
+    end_callbacks: Vec<EndCallback>,
+    report: TickReport,
+    stats: Stats,
 }
 
-// TODO add ability to pass seed
 impl Game {
-    pub fn new(team_count: u16, max_wave_count: u16) -> Game {
-        let num_teams = if !(1..=8).contains(&team_count) {
-            2
-        } else {
-            team_count
-        };
+    /// Set up a new game, with the initial wave of ships already in place
+    pub fn new(config: GameConfig) -> Result<Game, ConfigError> {
+        if !(1..=8).contains(&config.teams) {
+            return Err(ConfigError::Teams(config.teams));
+        }
 
-        let max_ships_per_wave = if !(1..=100).contains(&max_wave_count) {
-            20
-        } else {
-            max_wave_count
-        };
+        if !(1..=100).contains(&config.wave) {
+            return Err(ConfigError::Wave(config.wave));
+        }
 
-        Game {
-            over: Arc::new(AtomicBool::new(false)),
+        if config.arena.width == 0 || config.arena.height == 0 {
+            return Err(ConfigError::Arena(config.arena));
+        }
+
+        config.rules.validate()?;
+
+        if let Some(win_condition) = &config.win_condition {
+            win_condition.validate()?;
+
+            // nobody to take out
+            if matches!(win_condition, WinCondition::Kills { .. }) && config.teams < 2 {
+                return Err(ConfigError::Rule("match", "needs at least 2 teams".into()));
+            }
+        }
+
+        let num_teams = config.teams;
+        let max_ships_per_wave = config.wave;
+        let seed = config.seed.unwrap_or_else(random);
+
+        let mut game = Game {
             num_teams,
             max_ships_per_wave,
             entities: Vec::with_capacity((num_teams * max_ships_per_wave).into()),
             new_entities: Vec::new(),
             ship_count: 0,
-            rng: thread_rng(),
+            waves: 0,
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+            arena: config.arena,
+            brains: Brains::from_rules(&config.rules),
+            rules: config.rules,
+            win_condition: config.win_condition,
+            map: config.map,
+            battle_started: false,
+            ticks: 0,
+            callbacks: Vec::new(),
+            end_callbacks: Vec::new(),
+            report: TickReport::default(),
+            stats: Stats::default(),
+        };
+
+        game.place_obstacles();
+
+        // initial wave, which is reported along with the first tick
+        game.reinforce();
+
+        Ok(game)
+    }
+
+    pub fn entities(&self) -> &Entities {
+        &self.entities
+    }
+
+    pub fn arena(&self) -> Arena {
+        self.arena
+    }
+
+    pub fn rules(&self) -> &Rules {
+        &self.rules
+    }
+
+    /// The config this game was set up with, including the seed that was picked
+    /// if none was given, so that the same battle can be played again
+    pub fn config(&self) -> GameConfig {
+        GameConfig {
+            teams: self.num_teams,
+            wave: self.max_ships_per_wave,
+            seed: Some(self.seed),
+            arena: self.arena,
+            rules: self.rules.clone(),
+            win_condition: self.win_condition,
+            map: self.map.clone(),
         }
     }
 
-    fn before_game(self) -> Game {
-        hide_cursor();
+    /// Change the size of the battlefield, bringing ships back within bounds
+    /// and getting rid of anything else that is left outside.
+    /// The arena never shrinks below a single cell.
+    pub fn resize(&mut self, arena: Arena) {
+        let arena = Arena {
+            width: arena.width.max(1),
+            height: arena.height.max(1),
+        };
+        self.arena = arena;
+
+        for entity in &mut self.entities {
+            if let Entity::Ship(ship) = entity {
+                ship.confine(&mut self.rng, arena);
+            }
+        }
+
+        self.entities.retain(|entity| {
+            matches!(entity, Entity::Ship(_)) || arena.contains(entity.get_position())
+        });
+        self.new_entities
+            .retain(|entity| arena.contains(entity.get_position()));
+    }
+
+    /// Put a brain of your own in charge of the ships of a team, in place of the one from the rules
+    pub fn set_brain<B: ShipBrain + 'static>(&mut self, team: Team, brain: B) {
+        self.brains.set(team, Box::new(brain));
+    }
+
+    pub fn win_condition(&self) -> Option<WinCondition> {
+        self.win_condition
+    }
+
+    /// Number of ticks that have passed since the game started
+    pub fn ticks(&self) -> u64 {
+        self.ticks
+    }
 
-        let over = Arc::clone(&self.over);
+    /// How each team has fared so far
+    pub fn stats(&self) -> &Stats {
+        &self.stats
+    }
 
-        ctrlc::set_handler(move || over.store(true, Relaxed)).expect("Couldn't set SIGINT handler");
+    /// Register a callback to be run at the end of every tick
+    pub fn on_tick<F>(&mut self, callback: F)
+    where
+        F: FnMut(&Game, &TickReport) + 'static,
+    {
+        self.callbacks.push(Box::new(callback));
+    }
 
-        // initial wave
-        self.reinforce()
+    /// Register a callback to be run once, when the game is over
+    pub fn on_end<F>(&mut self, callback: F)
+    where
+        F: FnOnce(&Game, &TickReport) + 'static,
+    {
+        self.end_callbacks.push(Box::new(callback));
     }
 
-    fn reinforce(mut self) -> Game {
+    /// Lay out the obstacles from the map, and scatter the asteroids from the rules around them
+    fn place_obstacles(&mut self) {
+        let mut cells: Vec<Position> = self
+            .map
+            .obstacles
+            .iter()
+            .copied()
+            .filter(|&pos| self.arena.contains(pos))
+            .collect();
+        cells.extend(asteroids(self.rules.asteroids, &mut self.rng, self.arena));
+
+        cells.sort_unstable_by_key(|&Position(x, y)| (y, x));
+        cells.dedup();
+
+        self.entities
+            .extend(cells.into_iter().map(Entity::obstacle));
+    }
+
+    /// Send in a wave of ships from a random team
+    pub fn reinforce(&mut self) {
         let team = Team::from_rand(self.rng.gen_range(0..self.num_teams).into());
         let ship_count = self.rng.gen_range(0..self.max_ships_per_wave) + 1;
+        self.waves += 1;
+
+        self.report.events.push(Event::Reinforcement {
+            team,
+            ships: ship_count + 1,
+        });
 
         for _ in 0..=ship_count {
-            let ship = Entity::ship(team);
+            let class = self.pick_class();
+            let ship = Entity::ship(
+                team,
+                self.ship_count,
+                class,
+                &mut self.rng,
+                self.arena,
+                &self.rules,
+            );
+            self.report.events.push(Event::ShipSpawned {
+                id: self.ship_count,
+                team,
+                class,
+                position: ship.get_position(),
+            });
             self.entities.push(ship);
 
             self.ship_count += 1;
         }
+    }
+
+    /// Drop a power-up with a random effect somewhere on the battlefield, unless the spot
+    /// turns out to be taken by an obstacle
+    fn spawn_power_up(&mut self) {
+        let position = random_position(&mut self.rng, self.arena);
+        let power_up = PowerUp::random(position, &mut self.rng);
+
+        let blocked = self
+            .entities
+            .iter()
+            .any(|e| matches!(e, Entity::Obstacle(_)) && e.get_position() == position);
 
-        self
+        if blocked {
+            return;
+        }
+
+        self.report.events.push(Event::PowerUpSpawned {
+            position,
+            effect: power_up.effect(),
+        });
+        self.entities.push(Entity::PowerUp(power_up));
+    }
+
+    /// A class for a new ship, drawn from the class mix in the rules
+    fn pick_class(&mut self) -> ShipClass {
+        let total: u32 = self.rules.classes.values().sum();
+
+        if total == 0 {
+            return ShipClass::Fighter;
+        }
+
+        let mut roll = self.rng.gen_range(0..total);
+
+        for (&class, &weight) in &self.rules.classes {
+            if roll < weight {
+                return class;
+            }
+
+            roll -= weight;
+        }
+
+        unreachable!("the roll is always below the total weight")
     }
 
-    pub fn run_game(mut self) {
-        self = self.before_game();
+    /// Advance the simulation by one step and report what happened
+    pub fn tick(&mut self) -> TickReport {
+        // anything that happened since the last tick is reported along with this one
+        self.report.tick = self.ticks + 1;
 
-        while !self.over.load(Relaxed) {
-            clear();
+        self.take_turns();
+        self.check_collisions();
+        self.remove_entities();
+        self.append_new_entities();
 
-            self = self.take_turns().check_collisions().remove_entities();
+        // 0.5% chance of reinforcements, with the default odds
+        if self.rng.gen_range(0..self.rules.reinforcement_odds) == 0 && !self.out_of_waves() {
+            self.reinforce();
+        }
 
-            self.draw_game();
+        if self.rules.power_up_odds > 0 && self.rng.gen_range(0..self.rules.power_up_odds) == 0 {
+            self.spawn_power_up();
+        }
 
-            // 70 fps, because Rust is faster than Go
-            thread::sleep(Duration::from_millis(1000 / 70));
+        self.ticks += 1;
 
-            self.append_new_entities();
+        if get_teams_from_entities(&self.entities).len() > 1 {
+            self.battle_started = true;
+        }
+
+        let report = mem::take(&mut self.report);
+        self.stats.record(&report);
+        self.run_callbacks(&report);
+
+        report
+    }
 
-            // 0.5% chance of reinforcements
-            if self.rng.gen_range(0..200) == 0 {
-                self = self.reinforce();
+    fn run_callbacks(&mut self, report: &TickReport) {
+        let mut callbacks = mem::take(&mut self.callbacks);
+
+        for callback in &mut callbacks {
+            callback(self, report);
+        }
+
+        self.callbacks = callbacks;
+    }
+
+    /// Let the end callbacks know that the game is over.
+    /// The tick callbacks have already seen the last tick, and aren't run again.
+    pub fn end(&mut self) -> TickReport {
+        let report = TickReport {
+            tick: self.ticks,
+            events: vec![Event::GameOver {
+                ticks: self.ticks,
+                winner: self.winner(),
+            }],
+        };
+
+        self.stats.record(&report);
+
+        for callback in mem::take(&mut self.end_callbacks) {
+            callback(self, &report);
+        }
+
+        report
+    }
+
+    /// Whether at least two teams have met on the battlefield
+    pub fn has_started(&self) -> bool {
+        self.battle_started
+    }
+
+    /// Whether the battle has been fought down to a single team.
+    /// A game with only one team is decided from the start.
+    pub fn is_decided(&self) -> bool {
+        (self.battle_started || self.num_teams == 1)
+            && get_teams_from_entities(&self.entities).len() <= 1
+    }
+
+    /// Whether the match has been won, or ended in a draw.
+    /// Without a win condition, this is the same as [`Game::is_decided`].
+    pub fn is_over(&self) -> bool {
+        match self.win_condition {
+            None => self.is_decided(),
+            Some(WinCondition::LastStanding { .. }) => {
+                self.out_of_waves() && get_teams_from_entities(&self.entities).len() <= 1
+            }
+            Some(WinCondition::Kills { kills }) => {
+                self.stats.teams.values().any(|team| team.kills >= kills)
             }
+            Some(WinCondition::TimeLimit { ticks }) => self.ticks >= ticks,
         }
+    }
 
-        self.after_game()
+    /// The team that won the match, or the last team standing once the battle is decided
+    /// when there is no win condition
+    pub fn winner(&self) -> Option<Team> {
+        match self.win_condition {
+            None => self.last_standing(),
+            Some(_) if !self.is_over() => None,
+            Some(WinCondition::LastStanding { .. }) => self.last_standing(),
+            Some(WinCondition::Kills { .. }) | Some(WinCondition::TimeLimit { .. }) => {
+                self.leader()
+            }
+        }
+    }
+
+    fn last_standing(&self) -> Option<Team> {
+        if !self.battle_started && self.num_teams > 1 {
+            return None;
+        }
+
+        match get_teams_from_entities(&self.entities).as_slice() {
+            [winner] => Some(*winner),
+            _ => None,
+        }
+    }
+
+    /// The team with the most kills, then the most ships left, if there is no tie
+    fn leader(&self) -> Option<Team> {
+        let ships = get_ships_from_entities(&self.entities);
+        let score = |team: Team| {
+            let kills = self.stats.teams.get(&team).map_or(0, |stats| stats.kills);
+            let left = ships.iter().filter(|ship| ship.team() == team).count();
+
+            (kills, left)
+        };
+
+        let mut scores: Vec<_> = self
+            .stats
+            .teams
+            .keys()
+            .map(|&team| (score(team), team))
+            .collect();
+        scores.sort_unstable_by_key(|&(score, _)| Reverse(score));
+
+        match scores.as_slice() {
+            [(first, _), (second, _), ..] if first == second => None,
+            [(_, leader), ..] => Some(*leader),
+            [] => None,
+        }
     }
 
-    fn take_turns(mut self) -> Self {
-        let (entities, new_entity_options): (Vec<_>, Vec<_>) = self
+    /// Whether the last wave of reinforcements for the match has been sent in
+    fn out_of_waves(&self) -> bool {
+        match self.win_condition {
+            Some(WinCondition::LastStanding { waves }) => self.waves >= waves,
+            _ => false,
+        }
+    }
+
+    fn take_turns(&mut self) {
+        let index = SpatialIndex::new(&self.entities);
+        let world = World {
+            entities: &self.entities,
+            index: &index,
+            arena: self.arena,
+            rules: &self.rules,
+            brains: &self.brains,
+        };
+
+        let (entities, new_entity_lists): (Vec<_>, Vec<_>) = self
             .entities
             .iter()
-            .map(|entity| entity.clone().take_turn(&mut self.rng, &self.entities))
+            .map(|entity| entity.clone().take_turn(&mut self.rng, &world))
             .unzip();
 
-        self.entities = entities;
-        self.new_entities = new_entity_options.into_iter().flatten().collect::<Vec<_>>();
+        let mut new_entities = Vec::new();
 
-        self
+        let new_entity_pairs = entities
+            .iter()
+            .zip(new_entity_lists)
+            .flat_map(|(entity, list)| {
+                list.into_iter().map(move |new_entity| (entity, new_entity))
+            });
+
+        for (entity, new_entity) in new_entity_pairs {
+            match (entity, new_entity) {
+                (Entity::Ship(ship), Entity::Bullet(bullet)) => {
+                    self.report.events.push(Event::BulletFired {
+                        team: ship.team(),
+                        position: bullet.get_position(),
+                        direction: bullet.direction(),
+                    });
+                    new_entities.push(Entity::Bullet(bullet));
+                }
+                // launched by a carrier
+                (_, Entity::Ship(ship)) => {
+                    let ship = ship.with_id(self.ship_count);
+                    self.report.events.push(Event::ShipSpawned {
+                        id: ship.id(),
+                        team: ship.team(),
+                        class: ship.class(),
+                        position: ship.get_position(),
+                    });
+                    new_entities.push(Entity::Ship(ship));
+
+                    self.ship_count += 1;
+                }
+                (_, new_entity) => new_entities.push(new_entity),
+            }
+        }
+
+        self.entities = entities;
+        self.new_entities = new_entities;
     }
 
-    fn check_collisions(mut self) -> Self {
-        self.entities = self
-            .entities
-            .iter()
-            .map(|entity| {
-                self.entities
-                    .iter()
-                    .find(|other| entity != *other && collided(entity, *other))
-                    .map(|other| entity.clone().on_collide(other))
-                    .unwrap_or_else(|| entity.clone())
-            })
-            .collect();
+    fn check_collisions(&mut self) {
+        let index = SpatialIndex::new(&self.entities);
+        let contacts = find_contacts(&self.entities, &index);
+
+        for &(i, j) in &contacts {
+            let (a, b) = (&self.entities[i], &self.entities[j]);
+
+            match (a, b) {
+                (Entity::Ship(a), Entity::Ship(b)) if a.team() == b.team() => continue,
+                // flying past a teammate, without friendly fire
+                (Entity::Ship(ship), Entity::Bullet(bullet))
+                | (Entity::Bullet(bullet), Entity::Ship(ship))
+                    if ship.team() == bullet.team() && !self.rules.friendly_fire =>
+                {
+                    continue
+                }
+                (Entity::Ship(ship), Entity::PowerUp(power_up))
+                | (Entity::PowerUp(power_up), Entity::Ship(ship)) => {
+                    self.report.events.push(Event::PowerUpTaken {
+                        id: ship.id(),
+                        team: ship.team(),
+                        position: power_up.get_position(),
+                        effect: power_up.effect(),
+                    });
+
+                    continue;
+                }
+                // nothing else touches power-ups
+                (Entity::PowerUp(_), _) | (_, Entity::PowerUp(_)) => continue,
+                // only bullets running into obstacles are worth mentioning
+                (Entity::Obstacle(_), other) | (other, Entity::Obstacle(_))
+                    if !matches!(other, Entity::Bullet(_)) =>
+                {
+                    continue
+                }
+                _ => (),
+            }
 
-        self
+            self.report.events.push(Event::Collision {
+                position: a.get_position(),
+                entities: (Participant::of(a), Participant::of(b)),
+            });
+        }
+
+        let entities = resolve_contacts(&self.entities, &contacts, &self.rules);
+
+        for (before, after) in self.entities.iter().zip(&entities) {
+            match (before, after) {
+                (Entity::Ship(before), Entity::Ship(after))
+                    if after.hp() < before.hp() && !after.should_remove() =>
+                {
+                    self.report.events.push(Event::ShipDamaged {
+                        id: after.id(),
+                        team: after.team(),
+                        position: after.get_position(),
+                        hp: after.hp(),
+                    });
+                }
+                (Entity::Explosion(before), Entity::Explosion(after))
+                    if before.health() > 0 && after.health() == 0 =>
+                {
+                    self.report.events.push(Event::ExplosionMerged {
+                        position: after.get_position(),
+                    });
+                }
+                _ => (),
+            }
+        }
+
+        self.entities = entities;
     }
 
-    fn remove_entities(mut self) -> Game {
+    fn remove_entities(&mut self) {
         let mut remaining_entities = Vec::new();
 
-        for entity in self.entities {
+        for entity in mem::take(&mut self.entities) {
             if !entity.should_remove() {
                 remaining_entities.push(entity);
 
                 continue;
             }
 
+            if let Entity::Ship(ship) = &entity {
+                self.report.events.push(Event::ShipDestroyed {
+                    id: ship.id(),
+                    team: ship.team(),
+                    position: ship.get_position(),
+                    by: ship.destroyed_by(),
+                });
+            }
+
             if entity.on_remove_explode() {
-                let explosion = Entity::explosion(entity.get_position());
+                let explosion = Entity::explosion(entity.get_position(), &self.rules);
+                self.report.events.push(Event::ExplosionCreated {
+                    position: explosion.get_position(),
+                });
                 remaining_entities.push(explosion)
             }
         }
 
         self.entities = remaining_entities;
-
-        self
     }
 
     fn append_new_entities(&mut self) {
         self.entities.append(&mut self.new_entities);
     }
 
-    fn draw_game(&self) {
-        for entity in &self.entities {
-            move_cursor(entity.get_position());
-            draw(entity.avatar());
-        }
-
-        let (width, _) = get_size();
-
-        let status = self.get_status();
-        move_cursor(Position((width / 2 - (status.len() as u16 / 2)).into(), 0));
-
-        draw(&status);
-
-        render();
-    }
-
-    fn get_status(&self) -> String {
+    pub fn get_status(&self) -> String {
         let current_ship_count = count_ships(&self.entities);
 
-        let message = format!(
+        let mut message = format!(
             "current ship count: {}     destroyed count: {}",
             count_ships(&self.entities),
-            self.ship_count - current_ship_count,
+            self.ship_count - u32::from(current_ship_count),
         );
 
-        message
-    }
-
-    fn after_game(self) {
-        clear();
-        show_cursor();
-
-        move_cursor(Position(1, 1));
-        draw(" 🔵  🔸 🔸 🔥");
-
-        move_cursor(Position(0, 3));
-
-        draw("See you again soon! 🦀");
-
-        move_cursor(Position(0, 5));
+        if let Some((team, top_gun)) = self.stats.top_gun() {
+            message += &format!("     top gun: {} {}", team.name(), top_gun);
+        }
 
-        render();
-        // os.Exit(0)
+        message
     }
 }
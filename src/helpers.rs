@@ -1,12 +1,47 @@
 use rand::prelude::*;
+use rand::rngs::StdRng;
+use serde::{Deserialize, Serialize};
 
 use crate::entities::{Entities, Entity, EntityBehavior};
-use crate::ship::Ship;
-use crate::terminal::get_size;
+use crate::ship::{Ship, Team};
 
-#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Position(pub i32, pub i32);
 
+/// The eight single steps, going around clockwise from the right
+pub const DIRECTIONS: [(i16, i16); 8] = [
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+];
+
+/// The dimensions of the battlefield
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Arena {
+    pub width: u16,
+    pub height: u16,
+}
+
+impl Arena {
+    /// Whether the position lies on the battlefield
+    pub fn contains(&self, pos: Position) -> bool {
+        (1..=self.width.into()).contains(&pos.0) && (1..=self.height.into()).contains(&pos.1)
+    }
+
+    /// The position on the battlefield closest to the given one
+    pub fn clamp(&self, pos: Position) -> Position {
+        Position(
+            pos.0.clamp(1, self.width.max(1).into()),
+            pos.1.clamp(1, self.height.max(1).into()),
+        )
+    }
+}
+
 pub fn collided<E1, E2>(entity_a: &E1, entity_b: &E2) -> bool
 where
     E1: EntityBehavior,
@@ -32,29 +67,44 @@ pub fn positions_are_same(a: Position, b: Position) -> bool {
     a.0 == b.0 && a.1 == b.1
 }
 
-pub fn random_position() -> Position {
-    let mut rng = thread_rng();
+/// Number of steps it takes to get from one position to another, moving diagonally when possible
+pub fn distance(a: Position, b: Position) -> i32 {
+    abs(a.0 - b.0).max(abs(a.1 - b.1))
+}
 
-    let (width, height) = get_size();
-    let x = rng.gen_range(0..width) + 1;
-    let y = rng.gen_range(0..height) + 2;
+/// The direction of a single step from one position toward another
+pub fn direction(from: Position, to: Position) -> (i16, i16) {
+    (
+        (to.0 - from.0).signum() as i16,
+        (to.1 - from.1).signum() as i16,
+    )
+}
+
+/// A random cell of the arena
+pub fn random_position(rng: &mut StdRng, arena: Arena) -> Position {
+    let Arena { width, height } = arena;
+    let x = rng.gen_range(1..=width);
+    let y = rng.gen_range(1..=height);
 
     Position(x.into(), y.into())
 }
 
-pub fn wall_position() -> Position {
-    let mut rng = thread_rng();
-    let (max_x, max_y) = get_size();
+/// A random cell along the edges of the arena
+pub fn wall_position(rng: &mut StdRng, arena: Arena) -> Position {
+    let Arena {
+        width: max_x,
+        height: max_y,
+    } = arena;
 
     match rng.gen_range(0..4) {
         // top
-        0 => Position(rng.gen_range(0..max_x).into(), 1),
+        0 => Position(rng.gen_range(1..=max_x).into(), 1),
         // bottom
-        1 => Position(rng.gen_range(0..max_x).into(), max_y.into()),
+        1 => Position(rng.gen_range(1..=max_x).into(), max_y.into()),
         // left
-        2 => Position(1, rng.gen_range(0..max_y).into()),
+        2 => Position(1, rng.gen_range(1..=max_y).into()),
         // right
-        _ => Position(max_x.into(), rng.gen_range(0..max_y).into()),
+        _ => Position(max_x.into(), rng.gen_range(1..=max_y).into()),
     }
 }
 
@@ -62,6 +112,18 @@ pub fn count_ships(entities: &Entities) -> u16 {
     get_ships_from_entities(entities).len() as u16
 }
 
+pub fn get_teams_from_entities(entities: &Entities) -> Vec<Team> {
+    let mut teams = Vec::new();
+
+    for ship in get_ships_from_entities(entities) {
+        if !teams.contains(&ship.team()) {
+            teams.push(ship.team());
+        }
+    }
+
+    teams
+}
+
 pub fn get_ships_from_entities(entities: &Entities) -> Vec<&Ship> {
     entities
         .iter()
@@ -0,0 +1,49 @@
+//! A zero-player battle that takes place in your terminal.
+//!
+//! Build a [`Game`] from a [`GameConfig`], then either hand it over to
+//! [`driver::run_game`] or drive it yourself one [`Game::tick`] at a time.
+
+pub mod asciicast;
+pub mod brain;
+pub mod bullet;
+pub mod charset;
+pub mod class;
+pub mod collision;
+pub mod dodge;
+pub mod driver;
+pub mod entities;
+pub mod events;
+pub mod explosion;
+pub mod flocking;
+pub mod game;
+pub mod helpers;
+pub mod obstacle;
+pub mod pathfinding;
+pub mod powerup;
+pub mod renderer;
+pub mod replay;
+pub mod report;
+pub mod rules;
+pub mod ship;
+pub mod spatial;
+pub mod stats;
+pub mod targeting;
+pub mod terminal;
+pub mod tournament;
+pub mod victory;
+pub mod world;
+
+pub use brain::{BrainKind, Intent, ShipBrain};
+pub use charset::Charset;
+pub use class::ShipClass;
+pub use entities::{Entities, Entity, EntityBehavior};
+pub use flocking::Formation;
+pub use game::{Game, GameConfig};
+pub use helpers::{Arena, Position};
+pub use obstacle::Map;
+pub use powerup::Effect;
+pub use report::{Event, Participant, TickReport};
+pub use rules::{ConfigError, Rules};
+pub use ship::{Ship, Team};
+pub use targeting::Targeting;
+pub use victory::WinCondition;
@@ -1,25 +1,304 @@
-mod bullet;
-mod entities;
-mod explosion;
-mod game;
-mod helpers;
-mod ship;
-mod terminal;
+use std::fs;
+use std::io;
+use std::num::ParseIntError;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::str::FromStr;
+use std::thread;
 
 use clap::Parser;
-use game::Game;
+use term_skirmish_rust::asciicast::record_asciicast;
+use term_skirmish_rust::events::{event_writer, stream_events};
+use term_skirmish_rust::replay::{record_replay, Replay};
+use term_skirmish_rust::stats::Stats;
+use term_skirmish_rust::tournament::Tournament;
+use term_skirmish_rust::{
+    driver, terminal, Arena, BrainKind, Charset, Formation, Game, GameConfig, Map, Rules,
+    ShipClass, Targeting, Team, WinCondition,
+};
 
 /// A skirmish in your terminal
 #[derive(Parser)]
 #[clap(author, version, about, long_about = None)]
 struct Args {
+    #[clap(subcommand)]
+    command: Option<Command>,
+
     /// Number of teams (1-8)
-    #[clap(short, long, default_value_t = 2)]
+    #[clap(short, long, default_value_t = 2, global = true)]
     teams: u16,
 
     /// Maximum number of ships in each reinforcement wave (1-100)
-    #[clap(short, long, default_value_t = 8)]
+    #[clap(short, long, default_value_t = 8, global = true)]
     wave: u16,
+
+    /// Seed for the random number generator, to replay the same battle
+    #[clap(short, long, global = true)]
+    seed: Option<u64>,
+
+    /// Characters used to draw the battle: ascii, unicode or emoji
+    #[clap(short, long, global = true, default_value_t = Charset::Emoji)]
+    charset: Charset,
+
+    /// TOML file with the rules of the game
+    #[clap(long, global = true)]
+    config: Option<PathBuf>,
+
+    #[clap(flatten)]
+    rules: RuleArgs,
+
+    /// Text file with obstacles to lay out, where every # is an obstacle
+    #[clap(long, value_name = "FILE", global = true)]
+    map: Option<PathBuf>,
+
+    /// Run the simulation without a terminal and print a summary at the end
+    #[clap(long)]
+    headless: bool,
+
+    /// Width of the arena in headless mode
+    #[clap(long, default_value_t = 80, global = true)]
+    width: u16,
+
+    /// Height of the arena in headless mode
+    #[clap(long, default_value_t = 24, global = true)]
+    height: u16,
+
+    /// Stop a headless battle, or a tournament match, after this many ticks
+    #[clap(long, global = true)]
+    ticks: Option<u64>,
+
+    /// Record the battle to a replay file
+    #[clap(long, value_name = "FILE")]
+    record: Option<PathBuf>,
+
+    /// Capture the battle as an asciinema recording, also in headless mode
+    #[clap(long, value_name = "FILE")]
+    asciicast: Option<PathBuf>,
+
+    /// Write everything that happens as JSON lines to a file, or - for stderr
+    #[clap(long, value_name = "FILE")]
+    events: Option<String>,
+
+    /// Play a match that ends on its own: last-standing[=WAVES] (10 waves by default),
+    /// kills=N or time=TICKS. The exit code is 10 plus the index of the winning team,
+    /// 1 for a draw, or 3 if the match was stopped before it was over.
+    #[clap(long = "match", value_name = "CONDITION", global = true)]
+    win_condition: Option<WinCondition>,
+
+    /// Write the end-of-battle statistics to a JSON file
+    #[clap(long, value_name = "FILE")]
+    stats_json: Option<PathBuf>,
+}
+
+#[derive(clap::Subcommand)]
+enum Command {
+    /// Play back a battle recorded with --record.
+    ///
+    /// Space pauses, the left and right arrows step through single ticks,
+    /// [ and ] seek ten seconds, + and - change the speed, 0 goes back to the start and q quits.
+    Replay {
+        /// The replay file
+        file: PathBuf,
+
+        /// Playback speed, relative to the recorded frame rate (1/16-16)
+        #[clap(long, default_value_t = 1.0, parse(try_from_str = parse_speed))]
+        speed: f64,
+    },
+
+    /// Play many headless matches in parallel and compare how the teams do.
+    ///
+    /// Each rule set plays one match for every seed, starting from --seed.
+    /// Matches are played with --match, or last-standing if it isn't given,
+    /// and are called a draw after --ticks, or 20000 ticks.
+    Tournament {
+        /// Number of matches to play with each rule set
+        #[clap(short = 'n', long, default_value_t = 100)]
+        matches: u32,
+
+        /// TOML file with a rule set to play with, can be given more than once.
+        /// Defaults to the rules from --config.
+        #[clap(long = "rules", value_name = "FILE")]
+        rule_sets: Vec<PathBuf>,
+
+        /// Number of matches to play at the same time, defaults to the number of CPUs
+        #[clap(long)]
+        threads: Option<usize>,
+    },
+}
+
+/// Overrides for individual rules from the config file
+#[derive(clap::Args)]
+struct RuleArgs {
+    /// Frames per second when running in a terminal
+    #[clap(long, global = true)]
+    fps: Option<u32>,
+
+    /// Reinforcements arrive on average once every this many ticks
+    #[clap(long, global = true)]
+    reinforcement_odds: Option<u32>,
+
+    /// Ticks a ship waits between moves
+    #[clap(long, global = true)]
+    move_power: Option<i32>,
+
+    /// Ticks a ship waits between shots
+    #[clap(long, global = true)]
+    bullet_power: Option<i32>,
+
+    /// Ticks a bullet waits between moves
+    #[clap(long, global = true)]
+    bullet_move_power: Option<i32>,
+
+    /// Chance that a ship with a loaded gun holds its fire anyway (0-1)
+    #[clap(long, global = true)]
+    wuss_out: Option<f64>,
+
+    /// Hits a ship can take before it goes down, when every hit does 1 damage
+    #[clap(long, global = true)]
+    ship_hp: Option<u16>,
+
+    /// Damage done by a bullet to the ship it hits
+    #[clap(long, global = true)]
+    bullet_damage: Option<u16>,
+
+    /// Damage done to both ships when enemies ram each other
+    #[clap(long, global = true)]
+    ram_damage: Option<u16>,
+
+    /// Number of asteroids scattered around the arena when the game starts
+    #[clap(long, global = true)]
+    asteroids: Option<u16>,
+
+    /// A power-up shows up on average once every this many ticks, or never when 0
+    #[clap(long, global = true)]
+    power_up_odds: Option<u32>,
+
+    /// Ticks the effect of a power-up lasts
+    #[clap(long, global = true)]
+    power_up_duration: Option<u32>,
+
+    /// Whether bullets hurt ships of the team that fired them: on or off
+    #[clap(long, value_name = "on|off", global = true, parse(try_from_str = parse_on_off))]
+    friendly_fire: Option<bool>,
+
+    /// Ticks an explosion lasts
+    #[clap(long, global = true)]
+    explosion_health: Option<u16>,
+
+    /// Ticks added to an explosion by anything that runs into it
+    #[clap(long, global = true)]
+    explosion_boost: Option<u16>,
+
+    /// Number of steps ahead that ships look out for bullets coming their way
+    #[clap(long, global = true)]
+    dodge_look_ahead: Option<u16>,
+
+    /// Chance that the ships of a team sidestep a bullet they see coming (0-1).
+    /// Can be given once for every team.
+    #[clap(long = "dodge", value_name = "TEAM=SKILL", global = true, parse(try_from_str = parse_team_setting))]
+    dodge: Vec<(Team, f64)>,
+
+    /// How the ships of a team fly together: loose, line, wedge or swarm.
+    /// Can be given once for every team.
+    #[clap(long = "formation", value_name = "TEAM=FORMATION", global = true, parse(try_from_str = parse_team_setting))]
+    formations: Vec<(Team, Formation)>,
+
+    /// Brain flying the ships of a team: classic or hunter. Can be given once for every team.
+    #[clap(long = "brain", value_name = "TEAM=BRAIN", global = true, parse(try_from_str = parse_team_setting))]
+    brains: Vec<(Team, BrainKind)>,
+
+    /// How the ships of a team pick which enemy to go after: nearest, weakest-cluster,
+    /// near-friends or random. Can be given once for every team.
+    #[clap(long = "targeting", value_name = "TEAM=STRATEGY", global = true, parse(try_from_str = parse_team_setting))]
+    targeting: Vec<(Team, Targeting)>,
+
+    /// How often a class of ship shows up in reinforcement waves, relative to the others:
+    /// scout, fighter, tank, sniper or carrier. Can be given once for every class.
+    #[clap(long = "class", value_name = "CLASS=WEIGHT", global = true, parse(try_from_str = parse_class_weight))]
+    classes: Vec<(ShipClass, u32)>,
+}
+
+impl RuleArgs {
+    fn apply(&self, rules: &mut Rules) {
+        macro_rules! apply {
+            ($($field:ident),+) => {
+                $(if let Some(value) = self.$field {
+                    rules.$field = value;
+                })+
+            };
+        }
+
+        apply!(
+            fps,
+            reinforcement_odds,
+            move_power,
+            bullet_power,
+            bullet_move_power,
+            wuss_out,
+            ship_hp,
+            bullet_damage,
+            ram_damage,
+            friendly_fire,
+            asteroids,
+            power_up_odds,
+            power_up_duration,
+            explosion_health,
+            explosion_boost,
+            dodge_look_ahead
+        );
+
+        rules.dodge.extend(self.dodge.iter().copied());
+        rules.formations.extend(self.formations.iter().copied());
+
+        rules.brains.extend(self.brains.iter().copied());
+        rules.targeting.extend(self.targeting.iter().copied());
+
+        rules.classes.extend(self.classes.iter().copied());
+    }
+}
+
+/// A setting for a single team, like `red=hunter`
+fn parse_team_setting<T>(s: &str) -> Result<(Team, T), String>
+where
+    T: FromStr,
+    T::Err: ToString,
+{
+    let (team, value) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected TEAM=VALUE, got `{}`", s))?;
+
+    let value = value.parse().map_err(|e: T::Err| e.to_string())?;
+
+    Ok((team.parse()?, value))
+}
+
+fn parse_on_off(s: &str) -> Result<bool, String> {
+    match s {
+        "on" => Ok(true),
+        "off" => Ok(false),
+        _ => Err(format!("expected on or off, got `{}`", s)),
+    }
+}
+
+/// How often a class shows up, like `tank=2`
+fn parse_class_weight(s: &str) -> Result<(ShipClass, u32), String> {
+    let (class, weight) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected CLASS=WEIGHT, got `{}`", s))?;
+
+    let weight = weight.parse().map_err(|e: ParseIntError| e.to_string())?;
+
+    Ok((class.parse()?, weight))
+}
+
+fn parse_speed(s: &str) -> Result<f64, String> {
+    let speed: f64 = s.parse().map_err(|_| format!("`{}` isn't a number", s))?;
+
+    if !(driver::MIN_SPEED..=driver::MAX_SPEED).contains(&speed) {
+        return Err(format!("must be between 1/16 and 16, got {}", s));
+    }
+
+    Ok(speed)
 }
 
 fn main() {
@@ -27,5 +306,168 @@ fn main() {
 }
 
 fn run(args: Args) {
-    Game::new(args.teams, args.wave).run_game()
+    if let Some(Command::Replay { file, speed }) = &args.command {
+        let replay = Replay::load(file).unwrap_or_else(|e| {
+            exit_with_error(format!("couldn't load replay {}: {}", file.display(), e))
+        });
+
+        return driver::play_replay(replay, args.charset, *speed);
+    }
+
+    if let Some(Command::Tournament {
+        matches,
+        rule_sets,
+        threads,
+    }) = &args.command
+    {
+        return run_tournament(&args, *matches, rule_sets, *threads);
+    }
+
+    let rules = load_rules(&args, args.config.as_deref());
+
+    let arena = if args.headless {
+        Arena {
+            width: args.width,
+            height: args.height,
+        }
+    } else {
+        args.charset.arena_for(terminal::get_size())
+    };
+
+    let mut game = Game::new(GameConfig {
+        teams: args.teams,
+        wave: args.wave,
+        seed: args.seed,
+        arena,
+        rules,
+        win_condition: args.win_condition,
+        map: load_map(&args),
+    })
+    .unwrap_or_else(|e| exit_with_error(e));
+
+    if let Some(path) = &args.record {
+        record_replay(&mut game, path).unwrap_or_else(|e| {
+            exit_with_error(format!("couldn't create {}: {}", path.display(), e))
+        });
+    }
+
+    if let Some(path) = &args.events {
+        let writer = event_writer(path)
+            .unwrap_or_else(|e| exit_with_error(format!("couldn't create {}: {}", path, e)));
+
+        stream_events(&mut game, writer);
+    }
+
+    if let Some(path) = &args.asciicast {
+        record_asciicast(&mut game, path, args.charset).unwrap_or_else(|e| {
+            exit_with_error(format!("couldn't create {}: {}", path.display(), e))
+        });
+    }
+
+    let game = if args.headless {
+        driver::run_headless(game, args.ticks)
+    } else {
+        driver::run_game(game, args.charset)
+    };
+
+    if let Some(path) = &args.stats_json {
+        write_stats(path, game.stats()).unwrap_or_else(|e| {
+            exit_with_error(format!("couldn't write {}: {}", path.display(), e))
+        });
+    }
+
+    if game.win_condition().is_some() {
+        let code = match game.winner() {
+            _ if !game.is_over() => 3,
+            Some(winner) => 10 + winner.index() as i32,
+            None => 1,
+        };
+
+        process::exit(code)
+    }
+}
+
+fn run_tournament(args: &Args, matches: u32, rule_sets: &[PathBuf], threads: Option<usize>) {
+    let threads = threads
+        .or_else(|| thread::available_parallelism().ok().map(|n| n.get()))
+        .unwrap_or(1);
+
+    let rule_sets: Vec<Option<&Path>> = if rule_sets.is_empty() {
+        vec![args.config.as_deref()]
+    } else {
+        rule_sets.iter().map(|path| Some(path.as_path())).collect()
+    };
+
+    let first_seed = args.seed.unwrap_or(0);
+
+    for (i, path) in rule_sets.into_iter().enumerate() {
+        let tournament = Tournament {
+            config: GameConfig {
+                teams: args.teams,
+                wave: args.wave,
+                seed: None,
+                arena: Arena {
+                    width: args.width,
+                    height: args.height,
+                },
+                rules: load_rules(args, path),
+                win_condition: Some(
+                    args.win_condition
+                        .unwrap_or(WinCondition::LastStanding { waves: 10 }),
+                ),
+                map: load_map(args),
+            },
+            matches,
+            first_seed,
+            max_ticks: args.ticks.unwrap_or(20000),
+            threads,
+        };
+
+        let standings = tournament.run().unwrap_or_else(|e| exit_with_error(e));
+
+        if i > 0 {
+            println!();
+        }
+
+        println!(
+            "{}: {} matches, seeds {}-{}",
+            path.map_or("default rules".into(), |path| path.display().to_string()),
+            matches,
+            first_seed,
+            first_seed + u64::from(matches.max(1) - 1),
+        );
+        print!("{}", standings);
+    }
+}
+
+/// The rules from a config file, or the defaults, with the overrides from the command line
+fn load_rules(args: &Args, path: Option<&Path>) -> Rules {
+    let mut rules = match path {
+        Some(path) => Rules::from_file(path).unwrap_or_else(|e| exit_with_error(e)),
+        None => Rules::default(),
+    };
+
+    args.rules.apply(&mut rules);
+
+    rules
+}
+
+/// The obstacles from the map file, if one was given
+fn load_map(args: &Args) -> Map {
+    match &args.map {
+        Some(path) => Map::from_file(path).unwrap_or_else(|e| exit_with_error(e)),
+        None => Map::default(),
+    }
+}
+
+fn write_stats(path: &Path, stats: &Stats) -> io::Result<()> {
+    let file = fs::File::create(path)?;
+    serde_json::to_writer_pretty(file, stats)?;
+
+    Ok(())
+}
+
+fn exit_with_error(error: impl std::fmt::Display) -> ! {
+    eprintln!("error: {}", error);
+    process::exit(2)
 }
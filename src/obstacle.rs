@@ -0,0 +1,154 @@
+use std::fs;
+use std::path::Path;
+
+use rand::prelude::*;
+use rand::rngs::StdRng;
+use serde::{Deserialize, Serialize};
+
+use crate::entities::{Entity, EntityBehavior};
+use crate::helpers::*;
+use crate::rules::{ConfigError, Rules};
+use crate::spatial::SpatialIndex;
+use crate::world::World;
+
+/// Most cells taken up by a single asteroid
+pub const ASTEROID_SIZE: usize = 6;
+
+/// A cell of rock that ships fly around and bullets can't get through
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Obstacle {
+    position: Position,
+}
+
+impl Obstacle {
+    pub fn new(pos: Position) -> Obstacle {
+        Obstacle { position: pos }
+    }
+}
+
+impl EntityBehavior for Obstacle {
+    fn get_position(&self) -> Position {
+        self.position
+    }
+
+    fn get_prev_position(&self) -> Position {
+        self.position
+    }
+
+    fn should_remove(&self) -> bool {
+        false
+    }
+
+    fn avatar(&self) -> &str {
+        "🪨"
+    }
+
+    fn take_turn(self, _: &mut StdRng, _: &World) -> (Obstacle, Vec<Entity>) {
+        (self, Vec::new())
+    }
+
+    // nothing can break it
+    fn on_collide(self, _: &[&Entity], _: &Rules) -> Obstacle {
+        self
+    }
+
+    fn on_remove_explode(&self) -> bool {
+        false
+    }
+}
+
+/// Obstacles laid out ahead of time
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Map {
+    pub obstacles: Vec<Position>,
+}
+
+impl Map {
+    /// Read a map from text, where every `#` is an obstacle.
+    /// The first character of the first line is the top left corner of the arena.
+    pub fn parse(text: &str) -> Map {
+        let obstacles = text
+            .lines()
+            .zip(1..)
+            .flat_map(|(line, y)| {
+                line.chars()
+                    .zip(1..)
+                    .filter(|&(c, _)| c == '#')
+                    .map(move |(_, x)| Position(x, y))
+            })
+            .collect();
+
+        Map { obstacles }
+    }
+
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Map, ConfigError> {
+        let path = path.as_ref();
+
+        let contents = fs::read_to_string(path)
+            .map_err(|e| ConfigError::Map(format!("{}: {}", path.display(), e)))?;
+
+        Ok(Map::parse(&contents))
+    }
+}
+
+/// The cells of a number of asteroids of up to `ASTEROID_SIZE` cells each, scattered around
+/// the arena. They stay clear of the edges, where ships come in.
+pub fn asteroids(count: u16, rng: &mut StdRng, arena: Arena) -> Vec<Position> {
+    let mut cells = Vec::new();
+
+    if arena.width < 3 || arena.height < 3 {
+        return cells;
+    }
+
+    let inside = |Position(x, y): Position| {
+        (2..i32::from(arena.width)).contains(&x) && (2..i32::from(arena.height)).contains(&y)
+    };
+
+    for _ in 0..count {
+        let start = Position(
+            rng.gen_range(2..i32::from(arena.width)),
+            rng.gen_range(2..i32::from(arena.height)),
+        );
+        let size = rng.gen_range(1..=ASTEROID_SIZE);
+        let mut asteroid = vec![start];
+
+        // grow the asteroid outward from a random cell at a time,
+        // giving up if it's boxed in
+        for _ in 0..size * 4 {
+            if asteroid.len() == size {
+                break;
+            }
+
+            let Position(x, y) = asteroid[rng.gen_range(0..asteroid.len())];
+            let (dx, dy) = DIRECTIONS[rng.gen_range(0..DIRECTIONS.len())];
+            let next = Position(x + i32::from(dx), y + i32::from(dy));
+
+            if inside(next) && !asteroid.contains(&next) {
+                asteroid.push(next);
+            }
+        }
+
+        cells.extend(asteroid);
+    }
+
+    cells
+}
+
+/// Whether an obstacle stands anywhere between the two positions, not counting the positions
+/// themselves, along the path a ship or bullet would take
+pub fn blocked_between(from: Position, to: Position, index: &SpatialIndex) -> bool {
+    let mut pos = from;
+
+    loop {
+        let (x, y) = direction(pos, to);
+        pos = Position(pos.0 + i32::from(x), pos.1 + i32::from(y));
+
+        if pos == to {
+            return false;
+        }
+
+        if index.is_blocked(pos) {
+            return true;
+        }
+    }
+}
@@ -0,0 +1,86 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use crate::helpers::*;
+use crate::obstacle::blocked_between;
+use crate::world::World;
+
+/// The next step on the way from one position to another, going around any obstacles.
+///
+/// Heads straight there when nothing is in the way, and otherwise takes the first step
+/// of the shortest way around. A destination inside an obstacle is reached once the ship
+/// is right next to it. `None` if there's no way to get any closer.
+pub fn route(from: Position, to: Position, world: &World) -> Option<(i16, i16)> {
+    let goal_blocked = world.index.is_blocked(to);
+
+    if from == to || (goal_blocked && distance(from, to) <= 1) {
+        return None;
+    }
+
+    let straight = direction(from, to);
+
+    if !blocked_between(from, to, world.index) {
+        return Some(straight);
+    }
+
+    // destinations off the edge of the arena are reached the same way
+    let goal_unreachable = !passable(to, world);
+    let is_goal = |pos: Position| pos == to || (goal_unreachable && distance(pos, to) <= 1);
+
+    // A* search, remembering the first step of the way to every cell
+    let width = usize::from(world.arena.width);
+    let cell = |Position(x, y): Position| (y - 1) as usize * width + (x - 1) as usize;
+    let mut steps_to = vec![i32::MAX; width * usize::from(world.arena.height)];
+    let mut open = BinaryHeap::from([Reverse((
+        distance(from, to),
+        distance(from, to),
+        0,
+        from.0,
+        from.1,
+        None,
+    ))]);
+
+    while let Some(Reverse((_, _, steps, x, y, first_step))) = open.pop() {
+        let pos = Position(x, y);
+
+        // already reached some shorter way
+        if pos != from && steps > steps_to[cell(pos)] {
+            continue;
+        }
+
+        for step in DIRECTIONS {
+            let next = offset(pos, step);
+            let first_step = first_step.or(Some(step));
+
+            if is_goal(next) {
+                return first_step;
+            }
+
+            if passable(next, world) && steps + 1 < steps_to[cell(next)] {
+                steps_to[cell(next)] = steps + 1;
+
+                // among equally good cells, the one closest to the destination goes first
+                let left = distance(next, to);
+                open.push(Reverse((
+                    steps + 1 + left,
+                    left,
+                    steps + 1,
+                    next.0,
+                    next.1,
+                    first_step,
+                )));
+            }
+        }
+    }
+
+    // cut off, so get as close as the obstacles allow
+    passable(offset(from, straight), world).then_some(straight)
+}
+
+fn offset(Position(x, y): Position, (dx, dy): (i16, i16)) -> Position {
+    Position(x + i32::from(dx), y + i32::from(dy))
+}
+
+fn passable(pos: Position, world: &World) -> bool {
+    world.arena.contains(pos) && !world.index.is_blocked(pos)
+}
@@ -0,0 +1,143 @@
+use std::fmt;
+
+use rand::prelude::*;
+use rand::rngs::StdRng;
+use serde::{Deserialize, Serialize};
+
+use crate::entities::{Entity, EntityBehavior};
+use crate::helpers::*;
+use crate::rules::Rules;
+use crate::ship::Ship;
+use crate::world::World;
+
+/// Ticks a power-up waits to be picked up before it fades away
+pub const LIFESPAN: u32 = 1000;
+
+/// Power-ups within this many steps of a ship catch its eye
+pub const SIGHT_RADIUS: i32 = 10;
+
+/// What a power-up does for the ship that picks it up, for a while
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Effect {
+    /// Reloads twice as fast
+    RapidFire,
+    /// Moves twice as fast
+    SpeedBoost,
+    /// Soaks up the next hit
+    Shield,
+    /// Fires three bullets at once, fanning out
+    SpreadShot,
+}
+
+impl Effect {
+    pub fn all() -> [Effect; 4] {
+        [
+            Effect::RapidFire,
+            Effect::SpeedBoost,
+            Effect::Shield,
+            Effect::SpreadShot,
+        ]
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            Effect::RapidFire => "rapid-fire",
+            Effect::SpeedBoost => "speed-boost",
+            Effect::Shield => "shield",
+            Effect::SpreadShot => "spread-shot",
+        }
+    }
+}
+
+impl fmt::Display for Effect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// A pickup floating around the arena, waiting for a ship to fly into it
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PowerUp {
+    position: Position,
+    effect: Effect,
+    ticks_left: u32,
+    taken: bool,
+}
+
+impl PowerUp {
+    pub fn new(pos: Position, effect: Effect) -> PowerUp {
+        PowerUp {
+            position: pos,
+            effect,
+            ticks_left: LIFESPAN,
+            taken: false,
+        }
+    }
+
+    /// A power-up with a random effect
+    pub fn random(pos: Position, rng: &mut StdRng) -> PowerUp {
+        let effects = Effect::all();
+
+        PowerUp::new(pos, effects[rng.gen_range(0..effects.len())])
+    }
+
+    pub fn effect(&self) -> Effect {
+        self.effect
+    }
+}
+
+impl EntityBehavior for PowerUp {
+    fn get_position(&self) -> Position {
+        self.position
+    }
+
+    fn get_prev_position(&self) -> Position {
+        self.position
+    }
+
+    fn should_remove(&self) -> bool {
+        self.taken || self.ticks_left == 0
+    }
+
+    fn avatar(&self) -> &str {
+        match self.effect {
+            Effect::RapidFire => "⚡",
+            Effect::SpeedBoost => "💨",
+            Effect::Shield => "💠",
+            Effect::SpreadShot => "🌟",
+        }
+    }
+
+    fn take_turn(mut self, _: &mut StdRng, _: &World) -> (PowerUp, Vec<Entity>) {
+        self.ticks_left = self.ticks_left.saturating_sub(1);
+
+        (self, Vec::new())
+    }
+
+    // every ship that flies into it at the same time gets the effect
+    fn on_collide(mut self, others: &[&Entity], _: &Rules) -> PowerUp {
+        if others.iter().any(|e| matches!(e, Entity::Ship(_))) {
+            self.taken = true;
+        }
+
+        self
+    }
+
+    fn on_remove_explode(&self) -> bool {
+        false
+    }
+}
+
+/// The closest power-up within sight of the ship, if there is one
+pub fn nearby_power_up(ship: &Ship, world: &World) -> Option<Position> {
+    let position = ship.get_position();
+
+    world
+        .index
+        .power_ups()
+        .iter()
+        .map(|&i| world.entities[i].get_position())
+        .filter(|&pos| distance(position, pos) <= SIGHT_RADIUS)
+        .min_by_key(|&pos| (distance(position, pos), pos.0, pos.1))
+}
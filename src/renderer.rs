@@ -0,0 +1,241 @@
+use unicode_width::UnicodeWidthStr;
+
+use crate::charset::{Charset, Glyph};
+use crate::entities::{Entities, EntityBehavior};
+use crate::game::Game;
+use crate::helpers::{Arena, Position};
+use crate::terminal::cursor_to;
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+enum Cell {
+    Empty,
+    Glyph {
+        symbol: String,
+        color: Option<&'static str>,
+    },
+    /// The right half of a double-width glyph in the cell to the left
+    Continuation,
+}
+
+/// Draws the game by keeping the previous frame around as a grid of cells
+/// and only sending the cells that changed to the terminal.
+pub struct Renderer {
+    charset: Charset,
+    columns: usize,
+    rows: usize,
+    prev_frame: Vec<Cell>,
+}
+
+impl Renderer {
+    /// The terminal is expected to be cleared before the first frame
+    pub fn new(arena: Arena, charset: Charset) -> Renderer {
+        // the status line takes up the first row, above the arena
+        let columns = usize::from(arena.width.max(1)) * usize::from(charset.cell_width());
+        let rows = usize::from(arena.height) + 1;
+
+        Renderer {
+            charset,
+            columns,
+            rows,
+            prev_frame: vec![Cell::Empty; columns * rows],
+        }
+    }
+
+    /// Number of terminal columns and rows the frames take up
+    pub fn size(&self) -> (usize, usize) {
+        (self.columns, self.rows)
+    }
+
+    /// Build the next frame of the game and return the escape sequences
+    /// needed to turn the previous frame into it
+    pub fn render(&mut self, game: &Game) -> String {
+        self.render_frame(game.entities(), &game.get_status())
+    }
+
+    /// Like [`Renderer::render`], for entities that aren't part of a running game
+    pub fn render_frame(&mut self, entities: &Entities, status: &str) -> String {
+        let mut frame = vec![Cell::Empty; self.columns * self.rows];
+        let cell_width = i32::from(self.charset.cell_width());
+
+        for entity in entities {
+            // the terminal treats column 0 the same as 1
+            let Position(x, y) = entity.get_position();
+            let column = (x.max(1) - 1) * cell_width + 1;
+
+            // below the status line
+            self.put(
+                &mut frame,
+                Position(column, y + 1),
+                self.charset.glyph(entity),
+            );
+        }
+
+        let x = (self.columns / 2).saturating_sub(status.len() / 2);
+
+        for (i, c) in status.chars().enumerate() {
+            let glyph = Glyph {
+                symbol: &c.to_string(),
+                color: None,
+            };
+
+            self.put(&mut frame, Position((x + i) as i32, 1), glyph);
+        }
+
+        let output = self.diff(&frame);
+        self.prev_frame = frame;
+
+        output
+    }
+
+    fn diff(&self, frame: &[Cell]) -> String {
+        let mut output = String::new();
+        let mut cursor = None;
+
+        for (i, (prev, next)) in self.prev_frame.iter().zip(frame).enumerate() {
+            if prev == next {
+                continue;
+            }
+
+            let (symbol, color) = match next {
+                Cell::Empty => (" ", None),
+                Cell::Glyph { symbol, color } => (symbol.as_str(), *color),
+                // already covered by the glyph to its left
+                Cell::Continuation => continue,
+            };
+
+            // skip the escape sequence when the cursor is already in place
+            if cursor != Some(i) {
+                let (x, y) = (i % self.columns + 1, i / self.columns + 1);
+                output.push_str(&cursor_to(Position(x as i32, y as i32)));
+            }
+
+            match color {
+                Some(color) => output.push_str(&format!("\x1B[{}m{}\x1B[0m", color, symbol)),
+                None => output.push_str(symbol),
+            }
+
+            let width = UnicodeWidthStr::width(symbol).max(1);
+            cursor = Some(i + width).filter(|next| next % self.columns != 0);
+        }
+
+        output
+    }
+
+    /// Place a glyph on the frame, at the given terminal column and row
+    fn put(&self, frame: &mut [Cell], pos: Position, glyph: Glyph) {
+        // the terminal treats column and row 0 the same as 1
+        let x = pos.0.max(1) as usize - 1;
+        let y = pos.1.max(1) as usize - 1;
+        let wide = UnicodeWidthStr::width(glyph.symbol) > 1;
+
+        // wide glyphs that don't fit are clipped, rather than wrapping onto the next line
+        if y >= self.rows || x >= self.columns || (wide && x + 1 >= self.columns) {
+            return;
+        }
+
+        let i = y * self.columns + x;
+
+        clear_cell(frame, i);
+        frame[i] = Cell::Glyph {
+            symbol: glyph.symbol.to_string(),
+            color: glyph.color,
+        };
+
+        if wide {
+            clear_cell(frame, i + 1);
+            frame[i + 1] = Cell::Continuation;
+        }
+    }
+}
+
+/// Empty a cell, along with the other half of the wide glyph it's part of, if any,
+/// since overwriting either half of a wide glyph wipes out the whole glyph
+fn clear_cell(frame: &mut [Cell], i: usize) {
+    match frame[i] {
+        Cell::Continuation => frame[i - 1] = Cell::Empty,
+        Cell::Glyph { .. } if frame.get(i + 1) == Some(&Cell::Continuation) => {
+            frame[i + 1] = Cell::Empty
+        }
+        _ => (),
+    }
+
+    frame[i] = Cell::Empty;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn glyph(symbol: &str) -> Glyph<'_> {
+        Glyph {
+            symbol,
+            color: None,
+        }
+    }
+
+    fn cell(symbol: &str) -> Cell {
+        Cell::Glyph {
+            symbol: symbol.to_string(),
+            color: None,
+        }
+    }
+
+    /// A renderer four columns wide, with the status line and a single row of arena
+    fn renderer() -> Renderer {
+        let arena = Arena {
+            width: 4,
+            height: 1,
+        };
+
+        Renderer::new(arena, Charset::Ascii)
+    }
+
+    #[test]
+    fn wide_glyph_over_half_of_another_wipes_it_out() {
+        let renderer = renderer();
+        let mut frame = vec![Cell::Empty; 8];
+
+        renderer.put(&mut frame, Position(1, 1), glyph("🔵"));
+        renderer.put(&mut frame, Position(2, 1), glyph("🔴"));
+
+        assert_eq!(
+            frame[..4],
+            [Cell::Empty, cell("🔴"), Cell::Continuation, Cell::Empty]
+        );
+
+        renderer.put(&mut frame, Position(3, 1), glyph("x"));
+
+        assert_eq!(
+            frame[..4],
+            [Cell::Empty, Cell::Empty, cell("x"), Cell::Empty]
+        );
+    }
+
+    #[test]
+    fn wide_glyph_in_the_last_column_is_clipped() {
+        let renderer = renderer();
+        let mut frame = vec![Cell::Empty; 8];
+
+        renderer.put(&mut frame, Position(4, 1), glyph("🔵"));
+        assert_eq!(frame, vec![Cell::Empty; 8]);
+
+        renderer.put(&mut frame, Position(4, 1), glyph("x"));
+        assert_eq!(frame[3], cell("x"));
+        assert_eq!(frame[4], Cell::Empty);
+    }
+
+    #[test]
+    fn cursor_only_moves_when_it_is_not_already_in_place() {
+        let renderer = renderer();
+        let mut frame = vec![Cell::Empty; 8];
+
+        renderer.put(&mut frame, Position(1, 1), glyph("🔵"));
+        renderer.put(&mut frame, Position(3, 1), glyph("x"));
+        // the cursor wraps at the end of the row, so it has to be moved again
+        renderer.put(&mut frame, Position(4, 1), glyph("y"));
+        renderer.put(&mut frame, Position(1, 2), glyph("z"));
+        renderer.put(&mut frame, Position(3, 2), glyph("w"));
+
+        assert_eq!(renderer.diff(&frame), "\x1B[1;1H🔵xy\x1B[2;1Hz\x1B[2;3Hw");
+    }
+}
@@ -0,0 +1,196 @@
+use std::cell::RefCell;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::rc::Rc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::bullet::Bullet;
+use crate::class::ShipClass;
+use crate::entities::{Entities, Entity, EntityBehavior};
+use crate::explosion::Explosion;
+use crate::game::{Game, GameConfig};
+use crate::helpers::Position;
+use crate::powerup::{Effect, PowerUp};
+use crate::rules::Rules;
+use crate::ship::{Ship, Team};
+
+/// Bumped whenever the file format changes in a way older versions can't read
+const VERSION: u32 = 6;
+
+/// The first line of a replay file
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Header {
+    pub version: u32,
+    /// The config of the recorded game, including its seed and arena size
+    pub config: GameConfig,
+}
+
+/// What was on screen at the end of a single tick
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Frame {
+    pub tick: u64,
+    pub status: String,
+    pub sprites: Vec<Sprite>,
+}
+
+/// Just enough of an entity to draw it again
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Sprite {
+    Ship(Team, Position, ShipClass),
+    /// A ship that has taken a hit and lived
+    DamagedShip(Team, Position, ShipClass),
+    Bullet(Team, Position),
+    Explosion(Position),
+    Obstacle(Position),
+    PowerUp(Effect, Position),
+}
+
+impl Sprite {
+    fn from_entity(entity: &Entity) -> Sprite {
+        match entity {
+            Entity::Ship(ship) if ship.is_damaged() => {
+                Sprite::DamagedShip(ship.team(), ship.get_position(), ship.class())
+            }
+            Entity::Ship(ship) => Sprite::Ship(ship.team(), ship.get_position(), ship.class()),
+            Entity::Bullet(bullet) => Sprite::Bullet(bullet.team(), bullet.get_position()),
+            Entity::Explosion(explosion) => Sprite::Explosion(explosion.get_position()),
+            Entity::Obstacle(obstacle) => Sprite::Obstacle(obstacle.get_position()),
+            Entity::PowerUp(power_up) => {
+                Sprite::PowerUp(power_up.effect(), power_up.get_position())
+            }
+        }
+    }
+
+    fn to_entity(&self) -> Entity {
+        match *self {
+            Sprite::Ship(team, pos, class) => {
+                Entity::Ship(Ship::at(team, pos).with_class(class, &Rules::default()))
+            }
+            Sprite::DamagedShip(team, pos, class) => Entity::Ship(
+                Ship::at(team, pos)
+                    .with_class(class, &Rules::default())
+                    .with_hp(1, 2),
+            ),
+            Sprite::Bullet(team, pos) => Entity::Bullet(Bullet::new(pos, (0, 0), team, 1)),
+            Sprite::Explosion(pos) => Entity::Explosion(Explosion::new(pos, 1)),
+            Sprite::Obstacle(pos) => Entity::obstacle(pos),
+            Sprite::PowerUp(effect, pos) => Entity::PowerUp(PowerUp::new(pos, effect)),
+        }
+    }
+}
+
+impl Frame {
+    fn from_game(game: &Game) -> Frame {
+        Frame {
+            tick: game.ticks(),
+            status: game.get_status(),
+            sprites: game.entities().iter().map(Sprite::from_entity).collect(),
+        }
+    }
+
+    /// Stand-ins for the recorded entities, for drawing the frame
+    pub fn entities(&self) -> Entities {
+        self.sprites.iter().map(Sprite::to_entity).collect()
+    }
+}
+
+/// Writes a game to a replay file as it is played, one JSON line per tick
+pub struct Recorder {
+    writer: BufWriter<File>,
+}
+
+impl Recorder {
+    /// Start a replay file with the game's config and its current state
+    pub fn create<P: AsRef<Path>>(path: P, game: &Game) -> io::Result<Recorder> {
+        let mut recorder = Recorder {
+            writer: BufWriter::new(File::create(path)?),
+        };
+
+        let header = Header {
+            version: VERSION,
+            config: game.config(),
+        };
+
+        recorder.write_line(&header)?;
+        recorder.record(game)?;
+
+        Ok(recorder)
+    }
+
+    pub fn record(&mut self, game: &Game) -> io::Result<()> {
+        self.write_line(&Frame::from_game(game))
+    }
+
+    /// Make sure everything recorded so far is in the file
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    fn write_line<T: Serialize>(&mut self, value: &T) -> io::Result<()> {
+        serde_json::to_writer(&mut self.writer, value)?;
+        self.writer.write_all(b"\n")
+    }
+}
+
+/// Record every tick of the game to a replay file, which is flushed when the game ends
+pub fn record_replay<P: AsRef<Path>>(game: &mut Game, path: P) -> io::Result<()> {
+    let recorder = Rc::new(RefCell::new(Recorder::create(path, game)?));
+    let end_recorder = Rc::clone(&recorder);
+
+    game.on_tick(move |game, _| {
+        recorder
+            .borrow_mut()
+            .record(game)
+            .expect("Couldn't write to the replay file")
+    });
+
+    game.on_end(move |_, _| {
+        end_recorder
+            .borrow_mut()
+            .flush()
+            .expect("Couldn't write to the replay file")
+    });
+
+    Ok(())
+}
+
+/// A recorded game, loaded into memory for playing back
+pub struct Replay {
+    pub header: Header,
+    pub frames: Vec<Frame>,
+}
+
+impl Replay {
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Replay> {
+        let mut lines = BufReader::new(File::open(path)?).lines();
+
+        let header: Header = match lines.next() {
+            Some(line) => serde_json::from_str(&line?)?,
+            None => return Err(invalid_data("the replay file is empty")),
+        };
+
+        if header.version != VERSION {
+            return Err(invalid_data(&format!(
+                "unsupported replay version {}",
+                header.version
+            )));
+        }
+
+        let frames = lines
+            .map(|line| Ok(serde_json::from_str(&line?)?))
+            .collect::<io::Result<Vec<Frame>>>()?;
+
+        if frames.is_empty() {
+            return Err(invalid_data("the replay file has no frames"));
+        }
+
+        Ok(Replay { header, frames })
+    }
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
@@ -0,0 +1,142 @@
+use serde::Serialize;
+
+use crate::class::ShipClass;
+use crate::entities::Entity;
+use crate::helpers::Position;
+use crate::powerup::Effect;
+use crate::ship::Team;
+
+/// Something that happened during a tick
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event {
+    /// A wave of reinforcements arrived
+    Reinforcement {
+        team: Team,
+        ships: u16,
+    },
+    ShipSpawned {
+        id: u32,
+        team: Team,
+        class: ShipClass,
+        position: Position,
+    },
+    BulletFired {
+        team: Team,
+        position: Position,
+        direction: (i16, i16),
+    },
+    /// Two entities ran into each other. Teammates flying past each other don't count.
+    Collision {
+        position: Position,
+        entities: (Participant, Participant),
+    },
+    /// A ship took a hit and lived
+    ShipDamaged {
+        id: u32,
+        team: Team,
+        position: Position,
+        /// Hit points the ship has left
+        hp: u16,
+    },
+    ShipDestroyed {
+        id: u32,
+        team: Team,
+        position: Position,
+        /// What took the ship out, if anything did
+        by: Option<Participant>,
+    },
+    ExplosionCreated {
+        position: Position,
+    },
+    /// An explosion was swallowed up by a bigger one
+    ExplosionMerged {
+        position: Position,
+    },
+    PowerUpSpawned {
+        position: Position,
+        effect: Effect,
+    },
+    /// A ship flew into a power-up and got its effect
+    PowerUpTaken {
+        id: u32,
+        team: Team,
+        position: Position,
+        effect: Effect,
+    },
+    /// The game was stopped
+    GameOver {
+        ticks: u64,
+        winner: Option<Team>,
+    },
+}
+
+/// An entity taking part in an event
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Participant {
+    Bullet {
+        team: Team,
+        /// The ship that fired it, if it's known
+        shooter: Option<u32>,
+    },
+    Ship {
+        team: Team,
+        id: u32,
+    },
+    Explosion,
+    Obstacle,
+    PowerUp,
+}
+
+impl Participant {
+    pub fn of(entity: &Entity) -> Participant {
+        match entity {
+            Entity::Ship(ship) => Participant::Ship {
+                team: ship.team(),
+                id: ship.id(),
+            },
+            Entity::Bullet(bullet) => Participant::Bullet {
+                team: bullet.team(),
+                shooter: bullet.shooter(),
+            },
+            Entity::Explosion(_) => Participant::Explosion,
+            Entity::Obstacle(_) => Participant::Obstacle,
+            Entity::PowerUp(_) => Participant::PowerUp,
+        }
+    }
+}
+
+/// Everything that happened during a single call to [`Game::tick`](crate::Game::tick)
+#[derive(Clone, Debug, Default)]
+pub struct TickReport {
+    /// The tick this report describes, starting from 1
+    pub tick: u64,
+    pub events: Vec<Event>,
+}
+
+impl TickReport {
+    pub fn spawns(&self) -> impl Iterator<Item = &Event> {
+        self.events
+            .iter()
+            .filter(|e| matches!(e, Event::ShipSpawned { .. }))
+    }
+
+    pub fn shots(&self) -> impl Iterator<Item = &Event> {
+        self.events
+            .iter()
+            .filter(|e| matches!(e, Event::BulletFired { .. }))
+    }
+
+    pub fn kills(&self) -> impl Iterator<Item = &Event> {
+        self.events
+            .iter()
+            .filter(|e| matches!(e, Event::ShipDestroyed { .. }))
+    }
+
+    pub fn explosions(&self) -> impl Iterator<Item = &Event> {
+        self.events
+            .iter()
+            .filter(|e| matches!(e, Event::ExplosionCreated { .. }))
+    }
+}
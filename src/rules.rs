@@ -0,0 +1,217 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::brain::BrainKind;
+use crate::class::ShipClass;
+use crate::flocking::Formation;
+use crate::helpers::Arena;
+use crate::ship::Team;
+use crate::targeting::Targeting;
+
+/// The knobs that control how a battle plays out
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Rules {
+    /// Frames per second when running in a terminal
+    pub fps: u32,
+    /// Reinforcements arrive on average once every this many ticks
+    pub reinforcement_odds: u32,
+    /// Ticks a ship waits between moves
+    pub move_power: i32,
+    /// Ticks a ship waits between shots
+    pub bullet_power: i32,
+    /// Ticks a bullet waits between moves
+    pub bullet_move_power: i32,
+    /// Chance that a ship with a loaded gun holds its fire anyway
+    pub wuss_out: f64,
+    /// Hits a ship can take before it goes down, when every hit does 1 damage
+    pub ship_hp: u16,
+    /// Damage done by a bullet to the ship it hits
+    pub bullet_damage: u16,
+    /// Damage done to both ships when enemies ram each other
+    pub ram_damage: u16,
+    /// Whether bullets hurt ships of the team that fired them.
+    /// Without it, bullets fly right past teammates.
+    pub friendly_fire: bool,
+    /// How often each class of ship shows up in reinforcement waves, relative to the others.
+    /// When empty, every ship is a fighter.
+    pub classes: BTreeMap<ShipClass, u32>,
+    /// Number of asteroids scattered around the arena when the game starts
+    pub asteroids: u16,
+    /// A power-up shows up on average once every this many ticks, or never when 0
+    pub power_up_odds: u32,
+    /// Ticks the effect of a power-up lasts
+    pub power_up_duration: u32,
+    /// Ticks an explosion lasts
+    pub explosion_health: u16,
+    /// Ticks added to an explosion by anything that runs into it
+    pub explosion_boost: u16,
+    /// Number of steps ahead that ships look out for bullets coming their way
+    pub dodge_look_ahead: u16,
+    /// Chance that the ships of each team sidestep a bullet they see coming (0-1),
+    /// 0 for teams that aren't listed
+    pub dodge: BTreeMap<Team, f64>,
+    /// How the ships of each team fly together, loose for teams that aren't listed
+    pub formations: BTreeMap<Team, Formation>,
+    /// The brain flying the ships of each team, classic for teams that aren't listed
+    pub brains: BTreeMap<Team, BrainKind>,
+    /// How the ships of each team pick which enemy to go after, nearest for teams that aren't listed
+    pub targeting: BTreeMap<Team, Targeting>,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Rules {
+            // 70 fps, because Rust is faster than Go
+            fps: 70,
+            reinforcement_odds: 200,
+            move_power: 3,
+            bullet_power: 15,
+            bullet_move_power: 1,
+            wuss_out: 0.5,
+            ship_hp: 1,
+            bullet_damage: 1,
+            ram_damage: 1,
+            friendly_fire: true,
+            classes: BTreeMap::new(),
+            asteroids: 0,
+            power_up_odds: 0,
+            power_up_duration: 300,
+            explosion_health: 10,
+            explosion_boost: 100,
+            dodge_look_ahead: 4,
+            dodge: BTreeMap::new(),
+            formations: BTreeMap::new(),
+            brains: BTreeMap::new(),
+            targeting: BTreeMap::new(),
+        }
+    }
+}
+
+impl Rules {
+    /// Read rules from a TOML file. Anything left out keeps its default value.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Rules, ConfigError> {
+        let path = path.as_ref();
+
+        let contents = fs::read_to_string(path)
+            .map_err(|e| ConfigError::File(format!("{}: {}", path.display(), e)))?;
+
+        toml::from_str(&contents)
+            .map_err(|e| ConfigError::File(format!("{}: {}", path.display(), e)))
+    }
+
+    pub fn dodge(&self, team: Team) -> f64 {
+        self.dodge.get(&team).copied().unwrap_or(0.0)
+    }
+
+    pub fn formation(&self, team: Team) -> Formation {
+        self.formations.get(&team).copied().unwrap_or_default()
+    }
+
+    pub fn targeting(&self, team: Team) -> Targeting {
+        self.targeting.get(&team).copied().unwrap_or_default()
+    }
+
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.fps == 0 {
+            return Err(ConfigError::Rule("fps", "must be at least 1".into()));
+        }
+
+        if self.reinforcement_odds == 0 {
+            return Err(ConfigError::Rule(
+                "reinforcement_odds",
+                "must be at least 1".into(),
+            ));
+        }
+
+        for (name, power) in [
+            ("move_power", self.move_power),
+            ("bullet_power", self.bullet_power),
+            ("bullet_move_power", self.bullet_move_power),
+        ] {
+            if power < 0 {
+                return Err(ConfigError::Rule(name, "can't be negative".into()));
+            }
+        }
+
+        if !(0.0..=1.0).contains(&self.wuss_out) {
+            return Err(ConfigError::Rule(
+                "wuss_out",
+                "must be between 0 and 1".into(),
+            ));
+        }
+
+        if self.ship_hp == 0 {
+            return Err(ConfigError::Rule("ship_hp", "must be at least 1".into()));
+        }
+
+        if self
+            .dodge
+            .values()
+            .any(|skill| !(0.0..=1.0).contains(skill))
+        {
+            return Err(ConfigError::Rule("dodge", "must be between 0 and 1".into()));
+        }
+
+        if !self.classes.is_empty() && self.classes.values().all(|&weight| weight == 0) {
+            return Err(ConfigError::Rule(
+                "classes",
+                "needs at least one class with a weight above 0".into(),
+            ));
+        }
+
+        if self.explosion_health == 0 {
+            return Err(ConfigError::Rule(
+                "explosion_health",
+                "must be at least 1".into(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Why a game couldn't be set up
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ConfigError {
+    /// The number of teams is outside of 1-8
+    Teams(u16),
+    /// The size of a reinforcement wave is outside of 1-100
+    Wave(u16),
+    /// The arena has no room for any ships
+    Arena(Arena),
+    /// A rule has a value that doesn't make sense
+    Rule(&'static str, String),
+    /// The config file couldn't be read or parsed
+    File(String),
+    /// The map file couldn't be read
+    Map(String),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Teams(teams) => {
+                write!(f, "number of teams must be between 1 and 8, got {}", teams)
+            }
+            ConfigError::Wave(wave) => {
+                write!(f, "wave size must be between 1 and 100, got {}", wave)
+            }
+            ConfigError::Arena(arena) => write!(
+                f,
+                "arena must be at least 1x1, got {}x{}",
+                arena.width, arena.height
+            ),
+            ConfigError::Rule(name, reason) => write!(f, "{} {}", name, reason),
+            ConfigError::File(reason) => write!(f, "couldn't load config file {}", reason),
+            ConfigError::Map(reason) => write!(f, "couldn't load map {}", reason),
+        }
+    }
+}
+
+impl Error for ConfigError {}
@@ -1,12 +1,24 @@
-use std::collections::HashSet;
+use std::str::FromStr;
 
 use rand::prelude::*;
+use rand::rngs::StdRng;
+use serde::{Deserialize, Serialize};
 
 use crate::bullet::Bullet;
-use crate::entities::{Entities, Entity, EntityBehavior};
+use crate::class::ShipClass;
+use crate::dodge::{incoming_bullet, sidestep};
+use crate::entities::{Entity, EntityBehavior};
+use crate::flocking::{flock, waiting_for_formation, Formation};
 use crate::helpers::*;
-
-#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+use crate::pathfinding::route;
+use crate::powerup::Effect;
+use crate::report::Participant;
+use crate::rules::Rules;
+use crate::world::World;
+
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Team {
     BLUE,
     RED,
@@ -19,6 +31,37 @@ pub enum Team {
 }
 
 impl Team {
+    pub fn name(&self) -> &str {
+        match self {
+            Team::BLUE => "blue",
+            Team::RED => "red",
+            Team::YELLOW => "yellow",
+            Team::GREEN => "green",
+            Team::ORANGE => "orange",
+            Team::BROWN => "brown",
+            Team::PURPLE => "purple",
+            Team::WHITE => "white",
+        }
+    }
+
+    /// Position of the team in the order teams are picked in, starting from 0
+    pub fn index(&self) -> u32 {
+        *self as u32
+    }
+
+    pub fn all() -> [Team; 8] {
+        [
+            Team::BLUE,
+            Team::RED,
+            Team::YELLOW,
+            Team::GREEN,
+            Team::ORANGE,
+            Team::BROWN,
+            Team::PURPLE,
+            Team::WHITE,
+        ]
+    }
+
     pub fn from_rand(i: u32) -> Team {
         match i {
             0 => Team::BLUE,
@@ -33,8 +76,23 @@ impl Team {
     }
 }
 
-#[derive(Clone, PartialEq, Eq, Hash)]
+impl FromStr for Team {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Team::all()
+            .into_iter()
+            .find(|team| team.name() == s)
+            .ok_or_else(|| format!("unknown team `{}`", s))
+    }
+}
+
+/// How far off a carrier sends the ships it launches
+const LAUNCH_DISTANCE: i32 = 10;
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Ship {
+    id: u32,
     position: Position,
     prev_position: Position,
     destination: Position,
@@ -42,147 +100,329 @@ pub struct Ship {
     move_power: i32,
     bullet_power: i32,
     team: Team,
+    class: ShipClass,
+    hp: u16,
+    max_hp: u16,
+    waiting: bool,
+    destroyed_by: Option<Participant>,
+    /// Power-ups in effect, with the ticks they have left
+    effects: Vec<(Effect, u32)>,
 }
 
 impl Ship {
-    pub fn new(t: Team) -> Ship {
-        let wall_pos = wall_position();
+    pub fn new(
+        t: Team,
+        id: u32,
+        class: ShipClass,
+        rng: &mut StdRng,
+        arena: Arena,
+        rules: &Rules,
+    ) -> Ship {
+        let wall_pos = wall_position(rng, arena);
+        let hp = class.hp(rules.ship_hp);
 
         Ship {
+            id,
             position: wall_pos,
             prev_position: wall_pos,
-            destination: random_position(),
+            destination: random_position(rng, arena),
             alive: true,
-            move_power: 3,
-            bullet_power: thread_rng().gen_range(0..=10),
+            move_power: class.move_power(rules.move_power),
+            bullet_power: rng.gen_range(0..=class.bullet_power(rules.bullet_power).min(10)),
             team: t,
+            class,
+            hp,
+            max_hp: hp,
+            waiting: false,
+            destroyed_by: None,
+            effects: Vec::new(),
         }
     }
 
-    fn shoot(&mut self, rng: &mut ThreadRng, entities: &Entities) -> Option<Bullet> {
-        if self.bullet_power != 15 {
-            self.bullet_power += 1;
-
-            return None;
+    /// A ship sitting still at the given position
+    pub fn at(t: Team, pos: Position) -> Ship {
+        Ship {
+            id: 0,
+            position: pos,
+            prev_position: pos,
+            destination: pos,
+            alive: true,
+            move_power: 0,
+            bullet_power: 0,
+            team: t,
+            class: ShipClass::Fighter,
+            hp: 1,
+            max_hp: 1,
+            waiting: false,
+            destroyed_by: None,
+            effects: Vec::new(),
         }
+    }
 
-        self.bullet_power = 0;
+    pub fn team(&self) -> Team {
+        self.team
+    }
 
-        // wuss out
-        if rng.gen_bool(0.5) {
-            return None;
-        }
+    /// Number of the ship, counting every ship that has been sent into the game
+    pub fn id(&self) -> u32 {
+        self.id
+    }
 
-        let ships = get_ships_from_entities(entities);
+    pub fn class(&self) -> ShipClass {
+        self.class
+    }
 
-        if ships.is_empty() {
-            return None;
+    /// The same ship, with a different number
+    pub fn with_id(mut self, id: u32) -> Ship {
+        self.id = id;
+        self
+    }
+
+    /// The same ship, of a different class, with the hit points to match
+    pub fn with_class(mut self, class: ShipClass, rules: &Rules) -> Ship {
+        self.class = class;
+        self.hp = class.hp(rules.ship_hp);
+        self.max_hp = self.hp;
+        self
+    }
+
+    /// The same ship, with the given hit points left out of the given maximum
+    pub fn with_hp(mut self, hp: u16, max_hp: u16) -> Ship {
+        self.hp = hp;
+        self.max_hp = max_hp;
+        self
+    }
+
+    pub fn hp(&self) -> u16 {
+        self.hp
+    }
+
+    /// Whether the ship has taken a hit and lived
+    pub fn is_damaged(&self) -> bool {
+        self.hp < self.max_hp
+    }
+
+    /// Where the ship is headed
+    pub fn destination(&self) -> Position {
+        self.destination
+    }
+
+    /// Whether the ship can fire this tick
+    pub fn gun_loaded(&self, rules: &Rules) -> bool {
+        let mut reload = self.class.bullet_power(rules.bullet_power);
+
+        if self.has_effect(Effect::RapidFire) {
+            reload /= 2;
         }
 
-        let mut seen = HashSet::new();
+        self.bullet_power >= reload
+    }
 
-        loop {
-            // no one to shoot at
-            if seen.len() == ships.len() {
-                break;
-            }
+    /// Whether a power-up with the given effect is working for the ship
+    pub fn has_effect(&self, effect: Effect) -> bool {
+        self.effects.iter().any(|&(e, _)| e == effect)
+    }
 
-            let ship = ships.choose(rng).unwrap();
+    /// The same ship, under the effect of a power-up for the given number of ticks
+    pub fn with_effect(mut self, effect: Effect, ticks: u32) -> Ship {
+        self.power_up(effect, ticks);
+        self
+    }
 
-            // already seen
-            if seen.contains(ship) {
-                continue;
-            }
+    /// Start the effect of a power-up, or start it over if it's already working
+    fn power_up(&mut self, effect: Effect, ticks: u32) {
+        self.effects.retain(|&(e, _)| e != effect);
+        self.effects.push((effect, ticks));
+    }
 
-            // same team
-            if self.team == ship.team {
-                seen.insert(ship);
+    fn wear_off_effects(&mut self) {
+        for (_, ticks) in &mut self.effects {
+            *ticks = ticks.saturating_sub(1);
+        }
 
-                continue;
-            }
+        self.effects.retain(|&(_, ticks)| ticks > 0);
+    }
 
-            if positions_are_same(self.position, ship.position) {
-                seen.insert(ship);
+    /// What took the ship out, if it has been destroyed by something
+    pub fn destroyed_by(&self) -> Option<Participant> {
+        self.destroyed_by
+    }
 
-                continue;
-            }
+    /// Pull the ship back onto a battlefield that has shrunk,
+    /// and find it somewhere else to go if its destination is gone
+    pub fn confine(&mut self, rng: &mut StdRng, arena: Arena) {
+        self.position = arena.clamp(self.position);
+        self.prev_position = arena.clamp(self.prev_position);
 
-            let x_dis = abs(self.get_position().0 - ship.get_position().0);
-            let y_dis = abs(self.get_position().1 - ship.get_position().1);
+        if !arena.contains(self.destination) {
+            self.destination = random_position(rng, arena);
+        }
+    }
 
-            // no straight shot
-            if x_dis != 0 && y_dis != 0 && x_dis - y_dis != 0 {
-                seen.insert(ship);
+    /// Fire in the given direction if the gun is loaded, or keep loading it.
+    /// Carriers launch a ship in that direction instead.
+    fn shoot(&mut self, direction: Option<(i16, i16)>, world: &World) -> Vec<Entity> {
+        let rules = world.rules;
 
-                continue;
-            }
+        if !self.gun_loaded(rules) {
+            self.bullet_power += 1;
 
-            // now there must be a straight shot
-            // make bullet and fire
-            let mut x_pos = 0;
-            let mut y_pos = 0;
+            return Vec::new();
+        }
 
-            if self.get_position().0 > ship.get_position().0 {
-                x_pos = -1
-            } else if self.get_position().0 < ship.get_position().0 {
-                x_pos = 1
-            }
+        // a rock right in front of the gun would only soak up the shot, so save it
+        if let Some((x, y)) = direction {
+            let muzzle = Position(
+                self.position.0 + i32::from(x),
+                self.position.1 + i32::from(y),
+            );
 
-            if self.get_position().1 > ship.get_position().1 {
-                y_pos = -1
-            } else if self.get_position().1 < ship.get_position().1 {
-                y_pos = 1
+            if world.index.is_blocked(muzzle) {
+                return Vec::new();
             }
+        }
 
-            let pos = Position(
-                self.position.0 + x_pos as i32,
-                self.position.1 + y_pos as i32,
-            );
-            let bullet = Bullet::new(pos, (x_pos, y_pos));
+        self.bullet_power = 0;
+
+        let (x, y) = match direction {
+            Some(direction) => direction,
+            None => return Vec::new(),
+        };
+
+        if let Some(class) = self.class.launches() {
+            let mut ship = Ship::at(self.team, self.position).with_class(class, rules);
+            ship.move_power = class.move_power(rules.move_power);
+            ship.destination = world.arena.clamp(Position(
+                self.position.0 + i32::from(x) * LAUNCH_DISTANCE,
+                self.position.1 + i32::from(y) * LAUNCH_DISTANCE,
+            ));
 
-            return Some(bullet);
+            return vec![Entity::Ship(ship)];
         }
 
-        None
+        let directions = if self.has_effect(Effect::SpreadShot) {
+            // straight ahead, and 45° to either side
+            vec![
+                (x, y),
+                ((x - y).signum(), (y + x).signum()),
+                ((x + y).signum(), (y - x).signum()),
+            ]
+        } else {
+            vec![(x, y)]
+        };
+
+        directions
+            .into_iter()
+            .map(|(x, y)| {
+                let pos = Position(
+                    self.position.0 + i32::from(x),
+                    self.position.1 + i32::from(y),
+                );
+                let bullet = Bullet::new(pos, (x, y), self.team, rules.bullet_damage)
+                    .with_move_power(self.class.bullet_move_power(rules.bullet_move_power))
+                    .with_shooter(self.id);
+
+                Entity::Bullet(bullet)
+            })
+            .collect()
     }
 
-    fn move_ship(&mut self, rng: &mut ThreadRng, entities: &Entities) {
-        if self.move_power != 3 {
+    fn move_ship(&mut self, rng: &mut StdRng, world: &World) {
+        let mut engine = self.class.move_power(world.rules.move_power);
+
+        if self.has_effect(Effect::SpeedBoost) {
+            engine /= 2;
+        }
+
+        if self.move_power < engine {
             self.move_power += 1;
 
             return;
         }
 
         self.move_power = 0;
-        self.move_toward_destination();
 
-        if self.has_reached_destination() {
-            self.destination = self.get_destination(rng, entities)
+        match self.dodge(rng, world) {
+            Some(step) => self.step(step),
+            None => self.move_toward_destination(world),
         }
     }
 
-    fn get_destination(&self, rng: &mut ThreadRng, entities: &Entities) -> Position {
-        if rng.gen_bool(0.5) {
-            return random_position();
+    /// A step out of the way of an incoming bullet, if the ship sees one coming
+    fn dodge(&self, rng: &mut StdRng, world: &World) -> Option<(i16, i16)> {
+        let skill = world.rules.dodge(self.team);
+
+        if skill == 0.0 {
+            return None;
         }
 
-        for e in entities {
-            match e {
-                Entity::Ship(ship) if ship.team != self.team => {
-                    return ship.get_position();
-                }
-                _ => continue,
-            }
+        let bullet = incoming_bullet(self.position, self.team, world)?;
+
+        if !rng.gen_bool(skill) {
+            return None;
         }
 
-        random_position()
+        sidestep(self.position, self.team, bullet, rng, world)
+    }
+
+    fn step(&mut self, (x, y): (i16, i16)) {
+        self.prev_position = self.position;
+        self.position.0 += i32::from(x);
+        self.position.1 += i32::from(y);
     }
 
-    fn has_reached_destination(&self) -> bool {
+    pub fn has_reached_destination(&self) -> bool {
         positions_are_same(self.position, self.destination)
     }
 
-    fn move_toward_destination(&mut self) {
+    fn move_toward_destination(&mut self, world: &World) {
+        // a destination inside an obstacle is as good as reached once the ship is next to it
+        if world.index.is_blocked(self.destination)
+            && distance(self.position, self.destination) <= 1
+        {
+            self.destination = self.position;
+
+            return;
+        }
+
+        let formation = world.rules.formation(self.team);
+
+        if formation != Formation::Loose {
+            // the leader of a straggling formation only moves every other time
+            if waiting_for_formation(self, formation, world) {
+                self.waiting = !self.waiting;
+
+                if self.waiting {
+                    return;
+                }
+            }
+
+            let (x, y) = flock(self, formation, world);
+            let to = Position(
+                self.position.0 + i32::from(x),
+                self.position.1 + i32::from(y),
+            );
+
+            if world.index.is_blocked(to) {
+                // find a way around it, toward where the ship is headed
+                if let Some(step) = route(self.position, self.destination, world) {
+                    self.step(step);
+                }
+            } else if world.arena.contains(to) {
+                self.step((x, y));
+            }
+
+            return;
+        }
+
+        if world.index.has_obstacles() {
+            if let Some(step) = route(self.position, self.destination, world) {
+                self.step(step);
+            }
+
+            return;
+        }
+
         if self.position.0 < self.destination.0 {
             self.move_right()
         } else if self.position.0 > self.destination.0 {
@@ -219,6 +459,28 @@ impl Ship {
 
 impl EntityBehavior for Ship {
     fn avatar(&self) -> &str {
+        // every class but fighters shows its team by the color behind it, see `Charset::glyph`
+        match self.class {
+            ShipClass::Scout => return "🚀",
+            ShipClass::Tank => return "🐢",
+            ShipClass::Sniper => return "🎯",
+            ShipClass::Carrier => return "🛸",
+            ShipClass::Fighter => (),
+        }
+
+        if self.is_damaged() {
+            return match &self.team {
+                Team::BLUE => "🟦",
+                Team::BROWN => "🟫",
+                Team::GREEN => "🟩",
+                Team::ORANGE => "🟧",
+                Team::PURPLE => "🟪",
+                Team::RED => "🟥",
+                Team::WHITE => "⬜",
+                Team::YELLOW => "🟨",
+            };
+        }
+
         match &self.team {
             Team::BLUE => "🔵",
             Team::BROWN => "🟤",
@@ -243,20 +505,66 @@ impl EntityBehavior for Ship {
         !self.alive
     }
 
-    fn take_turn(mut self, rng: &mut ThreadRng, entities: &Entities) -> (Ship, Option<Entity>) {
-        self.move_ship(rng, entities);
+    fn take_turn(mut self, rng: &mut StdRng, world: &World) -> (Ship, Vec<Entity>) {
+        self.wear_off_effects();
+        self.move_ship(rng, world);
 
-        match self.shoot(rng, entities) {
-            Some(bullet) => (self, Some(Entity::Bullet(bullet))),
-            None => (self, None),
-        }
+        let intent = world.brains.get(self.team).think(&self, rng, world);
+        self.destination = intent.destination;
+
+        let launched = self.shoot(intent.fire, world);
+
+        (self, launched)
     }
 
-    fn on_collide(mut self, e: &Entity) -> Ship {
-        match e {
-            Entity::Ship(ship) if ship.team != self.team => self.alive = false,
-            Entity::Bullet(_) => self.alive = false,
-            _ => (),
+    fn on_collide(mut self, others: &[&Entity], rules: &Rules) -> Ship {
+        let mut hits: Vec<(&Entity, u16)> = others
+            .iter()
+            .map(|&e| match e {
+                // ramming hurts both sides
+                Entity::Ship(ship) if ship.team != self.team => (e, rules.ram_damage),
+                Entity::Bullet(bullet) if bullet.team() == self.team && !rules.friendly_fire => {
+                    (e, 0)
+                }
+                Entity::Bullet(bullet) => (e, bullet.damage()),
+                _ => (e, 0),
+            })
+            .filter(|&(_, damage)| damage > 0)
+            .collect();
+
+        // the shield soaks up the hardest hit, and goes down with it
+        if self.has_effect(Effect::Shield) {
+            let hardest = (0..hits.len()).max_by_key(|&i| (hits[i].1, Participant::of(hits[i].0)));
+
+            if let Some(hardest) = hardest {
+                hits.remove(hardest);
+                self.effects.retain(|&(e, _)| e != Effect::Shield);
+            }
+        }
+
+        // power-ups picked up now only help from here on
+        for other in others {
+            if let Entity::PowerUp(power_up) = other {
+                self.power_up(power_up.effect(), rules.power_up_duration);
+            }
+        }
+
+        let damage = hits
+            .iter()
+            .fold(0, |total: u16, &(_, damage)| total.saturating_add(damage));
+        self.hp = self.hp.saturating_sub(damage);
+
+        if self.hp > 0 {
+            return self;
+        }
+
+        // when several things hit the ship at once, the credit goes to
+        // the same one no matter which order they came in
+        let culprit = hits.iter().map(|&(e, _)| Participant::of(e)).min();
+
+        if let Some(culprit) = culprit {
+            self.alive = false;
+            self.destroyed_by = Some(culprit);
         }
 
         self
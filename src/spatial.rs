@@ -0,0 +1,101 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::entities::{Entities, Entity, EntityBehavior};
+use crate::helpers::Position;
+use crate::ship::Team;
+
+/// A spatial hash of the entities on the battlefield, built once per tick,
+/// so that collisions and targets can be looked up without scanning every entity.
+///
+/// Entities are referred to by their index in the `Entities` the index was built from.
+pub struct SpatialIndex {
+    cells: HashMap<Position, Vec<usize>>,
+    obstacles: HashSet<Position>,
+    power_ups: Vec<usize>,
+    ships_by_team: BTreeMap<Team, Vec<usize>>,
+}
+
+impl SpatialIndex {
+    pub fn new(entities: &Entities) -> SpatialIndex {
+        let mut index = SpatialIndex {
+            cells: HashMap::new(),
+            obstacles: HashSet::new(),
+            power_ups: Vec::new(),
+            ships_by_team: BTreeMap::new(),
+        };
+
+        for (i, entity) in entities.iter().enumerate() {
+            let Position(x, y) = entity.get_position();
+
+            index.cells.entry(Position(x, y)).or_default().push(i);
+
+            match entity {
+                Entity::Obstacle(_) => {
+                    index.obstacles.insert(Position(x, y));
+                }
+                Entity::PowerUp(_) => index.power_ups.push(i),
+                _ => (),
+            }
+
+            if let Entity::Ship(ship) = entity {
+                index.ships_by_team.entry(ship.team()).or_default().push(i);
+            }
+        }
+
+        index
+    }
+
+    /// Every entity at the given position
+    pub fn at(&self, pos: Position) -> &[usize] {
+        self.cells.get(&pos).map_or(&[], Vec::as_slice)
+    }
+
+    /// Whether an obstacle takes up the position
+    pub fn is_blocked(&self, pos: Position) -> bool {
+        self.obstacles.contains(&pos)
+    }
+
+    /// Whether there are any obstacles on the battlefield at all
+    pub fn has_obstacles(&self) -> bool {
+        !self.obstacles.is_empty()
+    }
+
+    /// Every power-up waiting to be picked up, in order
+    pub fn power_ups(&self) -> &[usize] {
+        &self.power_ups
+    }
+
+    /// Every entity that could have collided with the given entity,
+    /// either by sharing its position or by swapping places with it, in order
+    pub fn collision_candidates<E: EntityBehavior>(&self, entity: &E) -> Vec<usize> {
+        let mut candidates = self.at(entity.get_position()).to_vec();
+        candidates.extend_from_slice(self.at(entity.get_prev_position()));
+
+        candidates.sort_unstable();
+        candidates.dedup();
+
+        candidates
+    }
+
+    /// Every ship of the given team, in order
+    pub fn ships_of(&self, team: Team) -> &[usize] {
+        self.ships_by_team.get(&team).map_or(&[], Vec::as_slice)
+    }
+
+    /// Every ship that doesn't belong to the given team, team by team
+    pub fn enemy_ships(&self, team: Team) -> impl Iterator<Item = usize> + '_ {
+        self.ships_by_team
+            .iter()
+            .filter(move |(t, _)| **t != team)
+            .flat_map(|(_, ships)| ships.iter().copied())
+    }
+
+    /// Number of ships that don't belong to the given team
+    pub fn enemy_count(&self, team: Team) -> usize {
+        self.ships_by_team
+            .iter()
+            .filter(|(t, _)| **t != team)
+            .map(|(_, ships)| ships.len())
+            .sum()
+    }
+}
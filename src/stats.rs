@@ -0,0 +1,263 @@
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::report::{Event, Participant, TickReport};
+use crate::ship::Team;
+
+/// How each team fared over the course of a game
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct Stats {
+    pub ticks: u64,
+    pub winner: Option<Team>,
+    pub teams: BTreeMap<Team, TeamStats>,
+    /// Ships still flying, with their team and the tick they arrived on
+    #[serde(skip)]
+    alive: HashMap<u32, (Team, u64)>,
+    /// Enemy ships taken out by every ship that has taken out any
+    #[serde(skip)]
+    kills_by_ship: HashMap<u32, u32>,
+    /// Kills credited to ships so far, to tell which top gun got there first
+    #[serde(skip)]
+    credited: u64,
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct TeamStats {
+    pub spawned: u32,
+    pub lost: u32,
+    /// Enemy ships taken out by this team's ships and bullets
+    pub kills: u32,
+    /// The team's own ships taken out by its own bullets
+    pub teamkills: u32,
+    pub shots: u32,
+    /// Bullets that ran into an enemy ship
+    pub hits: u32,
+    /// Share of shots that hit, from 0 to 1
+    pub accuracy: f64,
+    /// Ticks the team's ships lasted on average, counting survivors once the game is over
+    pub average_lifespan: f64,
+    pub longest_lived: Option<Lifespan>,
+    /// The ship with the most kills on the team
+    pub top_gun: Option<TopGun>,
+    /// How many kills had been credited when the top gun got its tally
+    #[serde(skip)]
+    top_gun_since: u64,
+    #[serde(skip)]
+    lifespans: u64,
+    #[serde(skip)]
+    ended: u32,
+}
+
+/// How long a single ship lasted
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Lifespan {
+    pub ship: u32,
+    pub ticks: u64,
+    /// Whether the ship was still flying when the game ended
+    pub survived: bool,
+}
+
+/// The ship with the most kills on its team
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct TopGun {
+    pub ship: u32,
+    pub kills: u32,
+}
+
+impl Stats {
+    /// Take the events of a tick into account
+    pub fn record(&mut self, report: &TickReport) {
+        self.ticks = self.ticks.max(report.tick);
+
+        for event in &report.events {
+            match *event {
+                Event::ShipSpawned { id, team, .. } => {
+                    self.team(team).spawned += 1;
+                    self.alive.insert(id, (team, report.tick));
+                }
+                Event::BulletFired { team, .. } => self.team(team).shots += 1,
+                Event::Collision { entities, .. } => {
+                    if let Some(team) = hit(entities).or_else(|| hit((entities.1, entities.0))) {
+                        self.team(team).hits += 1;
+                    }
+                }
+                Event::ShipDestroyed { id, team, by, .. } => {
+                    self.team(team).lost += 1;
+                    self.end_lifespan(id, report.tick, false);
+
+                    let killer = match by {
+                        Some(Participant::Bullet { team, shooter }) => Some((team, shooter)),
+                        Some(Participant::Ship { team, id }) => Some((team, Some(id))),
+                        _ => None,
+                    };
+
+                    match killer {
+                        Some((killer, _)) if killer == team => self.team(team).teamkills += 1,
+                        Some((killer, ship)) => {
+                            self.team(killer).kills += 1;
+
+                            if let Some(ship) = ship {
+                                self.credit(killer, ship);
+                            }
+                        }
+                        None => (),
+                    }
+                }
+                Event::GameOver { ticks, winner } => {
+                    self.winner = winner;
+
+                    let mut survivors: Vec<u32> = self.alive.keys().copied().collect();
+                    survivors.sort_unstable();
+
+                    for id in survivors {
+                        self.end_lifespan(id, ticks, true);
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        for stats in self.teams.values_mut() {
+            stats.update();
+        }
+    }
+
+    fn team(&mut self, team: Team) -> &mut TeamStats {
+        self.teams.entry(team).or_default()
+    }
+
+    /// The ship with the most kills of them all, and its team.
+    /// Ties go to the ship that got there first.
+    pub fn top_gun(&self) -> Option<(Team, TopGun)> {
+        self.teams
+            .iter()
+            .filter_map(|(&team, stats)| Some((team, stats.top_gun?, stats.top_gun_since)))
+            .max_by_key(|&(_, top_gun, since)| (top_gun.kills, Reverse(since)))
+            .map(|(team, top_gun, _)| (team, top_gun))
+    }
+
+    /// Give a ship credit for a kill
+    fn credit(&mut self, team: Team, ship: u32) {
+        self.credited += 1;
+        let credited = self.credited;
+
+        let kills = self.kills_by_ship.entry(ship).or_default();
+        *kills += 1;
+
+        let top_gun = TopGun {
+            ship,
+            kills: *kills,
+        };
+        let stats = self.team(team);
+
+        if stats.top_gun.is_none_or(|t| top_gun.kills > t.kills) {
+            stats.top_gun = Some(top_gun);
+            stats.top_gun_since = credited;
+        }
+    }
+
+    fn end_lifespan(&mut self, id: u32, tick: u64, survived: bool) {
+        let (team, spawned) = match self.alive.remove(&id) {
+            Some(ship) => ship,
+            None => return,
+        };
+
+        let lifespan = Lifespan {
+            ship: id,
+            ticks: tick - spawned,
+            survived,
+        };
+
+        let stats = self.team(team);
+        stats.lifespans += lifespan.ticks;
+        stats.ended += 1;
+
+        if stats.longest_lived.is_none_or(|l| lifespan.ticks > l.ticks) {
+            stats.longest_lived = Some(lifespan);
+        }
+    }
+}
+
+/// The team of a bullet hitting an enemy ship
+fn hit(entities: (Participant, Participant)) -> Option<Team> {
+    match entities {
+        (Participant::Bullet { team, .. }, Participant::Ship { team: target, .. })
+            if team != target =>
+        {
+            Some(team)
+        }
+        _ => None,
+    }
+}
+
+impl TeamStats {
+    fn update(&mut self) {
+        if self.shots > 0 {
+            self.accuracy = f64::from(self.hits) / f64::from(self.shots);
+        }
+
+        if self.ended > 0 {
+            self.average_lifespan = self.lifespans as f64 / f64::from(self.ended);
+        }
+    }
+}
+
+impl fmt::Display for TopGun {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let plural = if self.kills == 1 { "" } else { "s" };
+
+        write!(f, "#{} ({} kill{})", self.ship, self.kills, plural)
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:<8} {:>7} {:>6} {:>6} {:>9} {:>6} {:>6} {:>9} {:>13}  {:<16} longest lived",
+            "team",
+            "spawned",
+            "lost",
+            "kills",
+            "teamkills",
+            "shots",
+            "hits",
+            "accuracy",
+            "avg lifespan",
+            "top gun"
+        )?;
+
+        for (team, stats) in &self.teams {
+            let longest = match stats.longest_lived {
+                Some(l) if l.survived => format!("#{} ({} ticks, survived)", l.ship, l.ticks),
+                Some(l) => format!("#{} ({} ticks)", l.ship, l.ticks),
+                None => "-".into(),
+            };
+            let top_gun = match stats.top_gun {
+                Some(t) => t.to_string(),
+                None => "-".into(),
+            };
+
+            writeln!(
+                f,
+                "{:<8} {:>7} {:>6} {:>6} {:>9} {:>6} {:>6} {:>8.1}% {:>13.1}  {:<16} {}",
+                team.name(),
+                stats.spawned,
+                stats.lost,
+                stats.kills,
+                stats.teamkills,
+                stats.shots,
+                stats.hits,
+                stats.accuracy * 100.0,
+                stats.average_lifespan,
+                top_gun,
+                longest,
+            )?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,182 @@
+use std::fmt;
+use std::str::FromStr;
+
+use rand::prelude::*;
+use rand::rngs::StdRng;
+use serde::{Deserialize, Serialize};
+
+use crate::entities::{Entity, EntityBehavior};
+use crate::helpers::*;
+use crate::ship::Ship;
+use crate::world::World;
+
+/// Ships within this many steps of each other count as the same cluster
+pub const CLUSTER_RADIUS: i32 = 5;
+
+/// How a ship picks which enemy to go after
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Targeting {
+    /// The enemy ship closest to us
+    #[default]
+    Nearest,
+    /// The enemy ship with the fewest teammates around it, to pick off stragglers
+    WeakestCluster,
+    /// The enemy ship closest to any of our teammates, to help them out
+    NearFriends,
+    /// Any enemy ship at all
+    Random,
+}
+
+impl Targeting {
+    /// The index of the enemy ship to go after, if there are any.
+    /// Ties go to the closest ship, then to the one that comes first.
+    pub fn pick(&self, ship: &Ship, rng: &mut StdRng, world: &World) -> Option<usize> {
+        let mut enemies = world.index.enemy_ships(ship.team());
+        let position = ship.get_position();
+        let from_ship = |&i: &usize| distance(position, world.entities[i].get_position());
+
+        match self {
+            Targeting::Nearest => nearest_enemy(ship, world),
+            Targeting::WeakestCluster => enemies.min_by_key(|&i| {
+                let enemy = &world.entities[i];
+                let cluster = match enemy {
+                    Entity::Ship(enemy) => teammates_around(enemy, world),
+                    _ => 0,
+                };
+
+                (cluster, from_ship(&i), i)
+            }),
+            Targeting::NearFriends => {
+                let friends: Vec<Position> = world
+                    .index
+                    .ships_of(ship.team())
+                    .iter()
+                    .map(|&i| world.entities[i].get_position())
+                    .filter(|&friend| friend != position)
+                    .collect();
+
+                enemies.min_by_key(|&i| {
+                    let enemy = world.entities[i].get_position();
+                    let from_friends = friends
+                        .iter()
+                        .map(|&friend| distance(friend, enemy))
+                        .min()
+                        .unwrap_or(0);
+
+                    (from_friends, from_ship(&i), i)
+                })
+            }
+            Targeting::Random => match world.index.enemy_count(ship.team()) {
+                0 => None,
+                count => enemies.nth(rng.gen_range(0..count)),
+            },
+        }
+    }
+}
+
+/// The enemy ship closest to the ship, looking at the cells around it one ring at a time.
+/// Once that has taken more looking than going through every enemy would,
+/// it goes through every enemy instead.
+fn nearest_enemy(ship: &Ship, world: &World) -> Option<usize> {
+    let enemies = world.index.enemy_count(ship.team());
+    let position = ship.get_position();
+    let mut looked_at = 0;
+
+    for radius in 0.. {
+        if looked_at > enemies {
+            return world
+                .index
+                .enemy_ships(ship.team())
+                .min_by_key(|&i| (distance(position, world.entities[i].get_position()), i));
+        }
+
+        let cells = ring(position, radius);
+        let closest = cells
+            .iter()
+            .flat_map(|&pos| world.index.at(pos))
+            .copied()
+            .filter(|&i| matches!(&world.entities[i], Entity::Ship(other) if other.team() != ship.team()))
+            .min();
+
+        if closest.is_some() {
+            return closest;
+        }
+
+        looked_at += cells.len();
+    }
+
+    unreachable!("every enemy is looked at sooner or later")
+}
+
+/// The cells exactly `radius` steps away from the position
+fn ring(Position(x, y): Position, radius: i32) -> Vec<Position> {
+    if radius == 0 {
+        return vec![Position(x, y)];
+    }
+
+    let mut cells = Vec::with_capacity(8 * radius as usize);
+
+    // the four sides, each from its corner going clockwise
+    for d in -radius..radius {
+        cells.push(Position(x + d, y - radius));
+        cells.push(Position(x + radius, y + d));
+        cells.push(Position(x - d, y + radius));
+        cells.push(Position(x - radius, y - d));
+    }
+
+    cells
+}
+
+/// Number of other ships of the same team within `CLUSTER_RADIUS` of the ship
+fn teammates_around(ship: &Ship, world: &World) -> usize {
+    let Position(x, y) = ship.get_position();
+    let mut count = 0;
+
+    for dx in -CLUSTER_RADIUS..=CLUSTER_RADIUS {
+        for dy in -CLUSTER_RADIUS..=CLUSTER_RADIUS {
+            count += world
+                .index
+                .at(Position(x + dx, y + dy))
+                .iter()
+                .filter(|&&i| match &world.entities[i] {
+                    Entity::Ship(other) => other.team() == ship.team(),
+                    _ => false,
+                })
+                .count();
+        }
+    }
+
+    // don't count the ship itself
+    count.saturating_sub(1)
+}
+
+impl FromStr for Targeting {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "nearest" => Ok(Targeting::Nearest),
+            "weakest-cluster" => Ok(Targeting::WeakestCluster),
+            "near-friends" => Ok(Targeting::NearFriends),
+            "random" => Ok(Targeting::Random),
+            _ => Err(format!(
+                "unknown targeting `{}`, expected nearest, weakest-cluster, near-friends or random",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Targeting {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Targeting::Nearest => "nearest",
+            Targeting::WeakestCluster => "weakest-cluster",
+            Targeting::NearFriends => "near-friends",
+            Targeting::Random => "random",
+        };
+
+        write!(f, "{}", name)
+    }
+}
@@ -1,12 +1,18 @@
-use std::io::{self, Write};
+use std::io::{self, Read, Write};
+use std::mem;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
 
 use terminal_size::terminal_size;
 use terminal_size::{Height, Width};
 
 use crate::helpers::Position;
 
+pub const HIDE_CURSOR: &str = "\x1B[?25l";
+pub const CLEAR: &str = "\x1B[2J";
+
 pub fn hide_cursor() {
-    print!("\x1B[?25l")
+    print!("{}", HIDE_CURSOR)
 }
 
 pub fn show_cursor() {
@@ -14,11 +20,16 @@ pub fn show_cursor() {
 }
 
 pub fn move_cursor(p: Position) {
-    print!("\x1B[{};{}H", p.1, p.0);
+    print!("{}", cursor_to(p));
+}
+
+/// The escape sequence that moves the cursor to the given position
+pub fn cursor_to(p: Position) -> String {
+    format!("\x1B[{};{}H", p.1, p.0)
 }
 
 pub fn clear() {
-    print!("\x1B[2J")
+    print!("{}", CLEAR)
 }
 
 pub fn draw(str: &str) {
@@ -29,9 +40,90 @@ pub fn render() {
     io::stdout().flush().unwrap();
 }
 
+/// Write a whole frame to the terminal in one go
+pub fn write_frame(frame: &str) {
+    let mut stdout = io::stdout().lock();
+
+    stdout.write_all(frame.as_bytes()).unwrap();
+    stdout.flush().unwrap();
+}
+
 /// Get the current size of the display
 pub fn get_size() -> (u16, u16) {
     terminal_size()
         .map(|(Width(width), Height(height))| (width, height - 1))
         .expect("Couldn't determine display size. Are you using a TTY?")
 }
+
+/// A key pressed by the user
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Key {
+    Char(char),
+    Up,
+    Down,
+    Right,
+    Left,
+}
+
+/// Keeps the terminal reading keys one at a time, without echoing them, until dropped
+pub struct RawMode {
+    original: libc::termios,
+}
+
+impl RawMode {
+    pub fn enable() -> io::Result<RawMode> {
+        // SAFETY: termios is a plain C struct, which tcgetattr fills in completely
+        let mut termios: libc::termios = unsafe { mem::zeroed() };
+
+        if unsafe { libc::tcgetattr(libc::STDIN_FILENO, &mut termios) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        let original = termios;
+
+        termios.c_lflag &= !(libc::ICANON | libc::ECHO);
+        termios.c_cc[libc::VMIN] = 1;
+        termios.c_cc[libc::VTIME] = 0;
+
+        if unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &termios) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(RawMode { original })
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &self.original) };
+    }
+}
+
+/// Read keys from stdin on a separate thread, so they can be polled without blocking
+pub fn read_keys() -> Receiver<Key> {
+    let (sender, receiver) = mpsc::channel();
+
+    thread::spawn(move || {
+        let mut bytes = io::stdin().lock().bytes().map_while(Result::ok);
+
+        while let Some(byte) = bytes.next() {
+            let key = match byte {
+                // escape sequence for the arrow keys
+                0x1B => match (bytes.next(), bytes.next()) {
+                    (Some(b'['), Some(b'A')) => Key::Up,
+                    (Some(b'['), Some(b'B')) => Key::Down,
+                    (Some(b'['), Some(b'C')) => Key::Right,
+                    (Some(b'['), Some(b'D')) => Key::Left,
+                    _ => continue,
+                },
+                byte => Key::Char(byte.into()),
+            };
+
+            if sender.send(key).is_err() {
+                break;
+            }
+        }
+    });
+
+    receiver
+}
@@ -0,0 +1,235 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::atomic::{AtomicU32, Ordering::Relaxed};
+use std::sync::Mutex;
+use std::thread;
+
+use crate::game::{Game, GameConfig};
+use crate::rules::ConfigError;
+use crate::ship::Team;
+
+/// A series of headless matches played with the same config, one for each seed
+#[derive(Clone, Debug)]
+pub struct Tournament {
+    /// The config every match is set up with. Its seed is replaced by the seed of the match.
+    pub config: GameConfig,
+    pub matches: u32,
+    pub first_seed: u64,
+    /// Matches that haven't been won by then are called a draw
+    pub max_ticks: u64,
+    pub threads: usize,
+}
+
+/// How a single match ended
+#[derive(Clone, Debug)]
+pub struct MatchResult {
+    pub seed: u64,
+    pub winner: Option<Team>,
+    pub ticks: u64,
+    pub kills: BTreeMap<Team, u32>,
+}
+
+/// The combined results of a tournament
+#[derive(Clone, Debug, Default)]
+pub struct Standings {
+    pub matches: u32,
+    pub draws: u32,
+    pub length: Sample,
+    pub teams: BTreeMap<Team, TeamRecord>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct TeamRecord {
+    pub wins: u32,
+    /// Kills per match
+    pub kills: Sample,
+}
+
+/// Running totals for a value measured once per match
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Sample {
+    count: u64,
+    sum: u64,
+    sum_of_squares: u64,
+}
+
+/// z-score for a 95% confidence level
+const Z: f64 = 1.96;
+
+impl Tournament {
+    /// Play every match, spread over the threads, and add up the results
+    pub fn run(&self) -> Result<Standings, ConfigError> {
+        // fail early instead of in every thread
+        Game::new(self.config.clone())?;
+
+        let next = AtomicU32::new(0);
+        let results = Mutex::new(Vec::with_capacity(self.matches as usize));
+
+        thread::scope(|scope| {
+            for _ in 0..self.threads.max(1) {
+                scope.spawn(|| loop {
+                    let i = next.fetch_add(1, Relaxed);
+
+                    if i >= self.matches {
+                        break;
+                    }
+
+                    let result = self.play(self.first_seed + u64::from(i));
+                    results.lock().unwrap().push(result);
+                });
+            }
+        });
+
+        let mut results = results.into_inner().unwrap();
+        results.sort_unstable_by_key(|result| result.seed);
+
+        let teams = (0..self.config.teams).map(|i| Team::from_rand(i.into()));
+
+        Ok(Standings::from_results(teams, &results))
+    }
+
+    /// Play a single match to the end
+    pub fn play(&self, seed: u64) -> MatchResult {
+        let mut game = Game::new(GameConfig {
+            seed: Some(seed),
+            ..self.config.clone()
+        })
+        .expect("config was checked before the tournament started");
+
+        while !game.is_over() && game.ticks() < self.max_ticks {
+            game.tick();
+        }
+
+        game.end();
+
+        let kills = game
+            .stats()
+            .teams
+            .iter()
+            .map(|(&team, stats)| (team, stats.kills))
+            .collect();
+
+        MatchResult {
+            seed,
+            winner: game.winner(),
+            ticks: game.ticks(),
+            kills,
+        }
+    }
+}
+
+impl Standings {
+    pub fn from_results(
+        teams: impl IntoIterator<Item = Team>,
+        results: &[MatchResult],
+    ) -> Standings {
+        let mut standings = Standings {
+            teams: teams
+                .into_iter()
+                .map(|team| (team, TeamRecord::default()))
+                .collect(),
+            ..Default::default()
+        };
+
+        for result in results {
+            standings.matches += 1;
+            standings.length.add(result.ticks);
+
+            match result.winner {
+                Some(winner) => standings.teams.entry(winner).or_default().wins += 1,
+                None => standings.draws += 1,
+            }
+
+            for (&team, record) in &mut standings.teams {
+                record
+                    .kills
+                    .add(result.kills.get(&team).copied().unwrap_or(0).into());
+            }
+        }
+
+        standings
+    }
+}
+
+impl TeamRecord {
+    /// Wilson score interval for the share of matches won
+    pub fn win_rate(&self, matches: u32) -> (f64, f64, f64) {
+        if matches == 0 {
+            return (0.0, 0.0, 0.0);
+        }
+
+        let n = f64::from(matches);
+        let p = f64::from(self.wins) / n;
+
+        let denominator = 1.0 + Z * Z / n;
+        let center = (p + Z * Z / (2.0 * n)) / denominator;
+        let margin = Z * (p * (1.0 - p) / n + Z * Z / (4.0 * n * n)).sqrt() / denominator;
+
+        (p, (center - margin).max(0.0), (center + margin).min(1.0))
+    }
+}
+
+impl Sample {
+    pub fn add(&mut self, value: u64) {
+        self.count += 1;
+        self.sum += value;
+        self.sum_of_squares += value * value;
+    }
+
+    pub fn mean(&self) -> f64 {
+        if self.count == 0 {
+            return 0.0;
+        }
+
+        self.sum as f64 / self.count as f64
+    }
+
+    /// Half the width of the 95% confidence interval around the mean
+    pub fn margin(&self) -> f64 {
+        if self.count < 2 {
+            return 0.0;
+        }
+
+        let n = self.count as f64;
+        let variance = (self.sum_of_squares as f64 - n * self.mean().powi(2)) / (n - 1.0);
+
+        Z * (variance.max(0.0) / n).sqrt()
+    }
+}
+
+impl fmt::Display for Standings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:<8} {:>6}  {:<26}  kills per match",
+            "team", "wins", "win rate (95% CI)"
+        )?;
+
+        for (team, record) in &self.teams {
+            let (rate, low, high) = record.win_rate(self.matches);
+
+            writeln!(
+                f,
+                "{:<8} {:>6}  {:<26}  {:.1} ± {:.1}",
+                team.name(),
+                record.wins,
+                format!(
+                    "{:.1}% ({:.1}% - {:.1}%)",
+                    rate * 100.0,
+                    low * 100.0,
+                    high * 100.0
+                ),
+                record.kills.mean(),
+                record.kills.margin(),
+            )?;
+        }
+
+        writeln!(f, "{:<8} {:>6}", "draws", self.draws)?;
+        writeln!(
+            f,
+            "average length: {:.1} ± {:.1} ticks",
+            self.length.mean(),
+            self.length.margin()
+        )
+    }
+}
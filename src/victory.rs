@@ -0,0 +1,90 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::rules::ConfigError;
+
+/// How a match is won
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "condition", rename_all = "snake_case")]
+pub enum WinCondition {
+    /// Reinforcements stop after this many waves, and the last team standing wins
+    LastStanding { waves: u32 },
+    /// The first team to take out this many enemy ships wins
+    Kills { kills: u32 },
+    /// The team with the most kills when time runs out wins,
+    /// with ties going to the team with more ships left
+    TimeLimit { ticks: u64 },
+}
+
+impl WinCondition {
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let valid = match *self {
+            WinCondition::LastStanding { waves } => waves > 0,
+            WinCondition::Kills { kills } => kills > 0,
+            WinCondition::TimeLimit { ticks } => ticks > 0,
+        };
+
+        if !valid {
+            return Err(ConfigError::Rule("match", "must be at least 1".into()));
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for WinCondition {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, value) = match s.split_once('=') {
+            Some((name, value)) => (name, Some(value)),
+            None => (s, None),
+        };
+
+        let number = |default: Option<u64>| -> Result<u64, String> {
+            let number = match (value, default) {
+                (Some(value), _) => value
+                    .parse()
+                    .map_err(|_| format!("`{}` isn't a number", value))?,
+                (None, Some(default)) => default,
+                (None, None) => return Err(format!("`{}` needs a value, like {}=20", name, name)),
+            };
+
+            if number == 0 {
+                return Err(format!("`{}` must be at least 1", name));
+            }
+
+            Ok(number)
+        };
+
+        let too_big = |_| format!("`{}` is too big", s);
+
+        match name {
+            "last-standing" => Ok(WinCondition::LastStanding {
+                waves: number(Some(10))?.try_into().map_err(too_big)?,
+            }),
+            "kills" => Ok(WinCondition::Kills {
+                kills: number(None)?.try_into().map_err(too_big)?,
+            }),
+            "time" => Ok(WinCondition::TimeLimit {
+                ticks: number(None)?,
+            }),
+            _ => Err(format!(
+                "unknown match `{}`, expected last-standing[=WAVES], kills=N or time=TICKS",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for WinCondition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WinCondition::LastStanding { waves } => write!(f, "last-standing={}", waves),
+            WinCondition::Kills { kills } => write!(f, "kills={}", kills),
+            WinCondition::TimeLimit { ticks } => write!(f, "time={}", ticks),
+        }
+    }
+}
@@ -0,0 +1,14 @@
+use crate::brain::Brains;
+use crate::entities::Entities;
+use crate::helpers::Arena;
+use crate::rules::Rules;
+use crate::spatial::SpatialIndex;
+
+/// A read-only view of the battlefield, handed to every entity when it takes its turn
+pub struct World<'a> {
+    pub entities: &'a Entities,
+    pub index: &'a SpatialIndex,
+    pub arena: Arena,
+    pub rules: &'a Rules,
+    pub brains: &'a Brains,
+}
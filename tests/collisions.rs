@@ -0,0 +1,220 @@
+mod common;
+
+use term_skirmish_rust::bullet::Bullet;
+use term_skirmish_rust::collision::resolve_collisions;
+use term_skirmish_rust::explosion::Explosion;
+use term_skirmish_rust::{
+    Effect, Entities, Entity, EntityBehavior, Participant, Position, Rules, Ship, Team,
+};
+
+use common::*;
+
+fn resolve(entities: &Entities) -> Entities {
+    resolve_collisions(entities, &Rules::default())
+}
+
+fn removed(entities: &Entities) -> Vec<bool> {
+    entities.iter().map(|e| e.should_remove()).collect()
+}
+
+#[test]
+fn ship_hit_by_two_bullets_takes_both_out() {
+    let entities = vec![ship(Team::BLUE, 5, 5), bullet(5, 5), bullet(5, 5)];
+
+    let resolved = resolve(&entities);
+
+    assert_eq!(removed(&resolved), vec![true, true, true]);
+}
+
+#[test]
+fn pileup_of_enemies_and_a_bullet_destroys_everyone() {
+    let entities = vec![
+        ship(Team::BLUE, 2, 2),
+        ship(Team::RED, 2, 2),
+        ship(Team::GREEN, 2, 2),
+        bullet(2, 2),
+        ship(Team::BLUE, 9, 9),
+    ];
+
+    let resolved = resolve(&entities);
+
+    assert_eq!(removed(&resolved), vec![true, true, true, true, false]);
+}
+
+#[test]
+fn teammates_sharing_a_cell_survive_but_a_bullet_among_them_hits_them_all() {
+    let teammates = vec![ship(Team::RED, 4, 1), ship(Team::RED, 4, 1)];
+    assert_eq!(removed(&resolve(&teammates)), vec![false, false]);
+
+    let with_bullet = vec![ship(Team::RED, 4, 1), bullet(4, 1), ship(Team::RED, 4, 1)];
+    assert_eq!(removed(&resolve(&with_bullet)), vec![true, true, true]);
+}
+
+#[test]
+fn bigger_explosion_swallows_smaller_one() {
+    let small = Entity::Explosion(Explosion::new(Position(3, 3), 10));
+    let big = Entity::Explosion(Explosion::new(Position(3, 3), 10))
+        .on_collide(&[&bullet(3, 3)], &Rules::default());
+
+    let resolved = resolve(&vec![small, big]);
+
+    assert_eq!(explosion_health(&resolved[0]), 0);
+    assert_eq!(explosion_health(&resolved[1]), 210);
+    assert_eq!(removed(&resolved), vec![true, false]);
+}
+
+#[test]
+fn equal_explosions_cancel_out() {
+    let entities = vec![
+        Entity::Explosion(Explosion::new(Position(1, 1), 10)),
+        Entity::Explosion(Explosion::new(Position(1, 1), 10)),
+    ];
+
+    assert_eq!(removed(&resolve(&entities)), vec![true, true]);
+}
+
+#[test]
+fn outcome_does_not_depend_on_entity_order() {
+    let big = Entity::Explosion(Explosion::new(Position(6, 6), 10))
+        .on_collide(&[&bullet(6, 6)], &Rules::default());
+    let entities = vec![
+        Entity::Explosion(Explosion::new(Position(6, 6), 10)),
+        ship(Team::YELLOW, 6, 6),
+        big,
+        bullet(6, 6),
+        ship(Team::PURPLE, 6, 6),
+    ];
+
+    let forward = resolve(&entities);
+
+    let mut reversed = entities.clone();
+    reversed.reverse();
+    let mut backward = resolve(&reversed);
+    backward.reverse();
+
+    assert_eq!(forward, backward);
+}
+
+#[test]
+fn sturdy_ship_survives_a_bullet() {
+    let entities = vec![ship_with_hp(Team::BLUE, 5, 5, 3), bullet(5, 5)];
+
+    let resolved = resolve(&entities);
+
+    assert_eq!(removed(&resolved), vec![false, true]);
+    assert_eq!(hp(&resolved[0]), 2);
+}
+
+#[test]
+fn ramming_damages_both_ships() {
+    let rules = Rules {
+        ram_damage: 2,
+        ..Rules::default()
+    };
+    let entities = vec![
+        ship_with_hp(Team::BLUE, 5, 5, 3),
+        ship_with_hp(Team::RED, 5, 5, 2),
+    ];
+
+    let resolved = resolve_collisions(&entities, &rules);
+
+    assert_eq!(removed(&resolved), vec![false, true]);
+    assert_eq!(hp(&resolved[0]), 1);
+}
+
+#[test]
+fn bullets_add_up_to_take_a_ship_down() {
+    let entities = vec![
+        ship_with_hp(Team::BLUE, 5, 5, 2),
+        bullet(5, 5),
+        bullet(5, 5),
+    ];
+
+    let resolved = resolve(&entities);
+
+    assert_eq!(removed(&resolved), vec![true, true, true]);
+}
+
+#[test]
+fn obstacle_absorbs_a_bullet_and_stays_put() {
+    let entities = vec![bullet(4, 4), Entity::obstacle(Position(4, 4))];
+
+    let resolved = resolve(&entities);
+
+    assert_eq!(removed(&resolved), vec![true, false]);
+    assert_eq!(resolved[1], entities[1]);
+}
+
+#[test]
+fn obstacle_does_not_feed_an_explosion() {
+    let entities = vec![
+        Entity::Explosion(Explosion::new(Position(2, 2), 10)),
+        Entity::obstacle(Position(2, 2)),
+    ];
+
+    let resolved = resolve(&entities);
+
+    assert_eq!(explosion_health(&resolved[0]), 10);
+}
+
+#[test]
+fn ship_picks_up_a_power_up() {
+    let entities = vec![ship(Team::RED, 5, 5), power_up(Effect::RapidFire, 5, 5)];
+
+    let resolved = resolve(&entities);
+
+    assert_eq!(removed(&resolved), vec![false, true]);
+    assert!(has_effect(&resolved[0], Effect::RapidFire));
+}
+
+#[test]
+fn bullet_flies_over_a_power_up() {
+    let entities = vec![bullet(5, 5), power_up(Effect::Shield, 5, 5)];
+
+    assert_eq!(removed(&resolve(&entities)), vec![false, false]);
+}
+
+#[test]
+fn shield_soaks_up_one_hit() {
+    let shielded =
+        Entity::Ship(Ship::at(Team::RED, Position(5, 5)).with_effect(Effect::Shield, 10));
+
+    let resolved = resolve(&vec![shielded.clone(), bullet(5, 5)]);
+    assert_eq!(removed(&resolved), vec![false, true]);
+    assert!(!has_effect(&resolved[0], Effect::Shield));
+
+    let resolved = resolve(&vec![shielded, bullet(5, 5), bullet(5, 5)]);
+    assert_eq!(removed(&resolved), vec![true, true, true]);
+}
+
+#[test]
+fn without_friendly_fire_bullets_fly_past_teammates() {
+    let rules = Rules {
+        friendly_fire: false,
+        ..Rules::default()
+    };
+    let entities = vec![ship(Team::WHITE, 4, 4), bullet(4, 4)];
+
+    let resolved = resolve_collisions(&entities, &rules);
+
+    assert_eq!(removed(&resolved), vec![false, false]);
+}
+
+#[test]
+fn kill_is_credited_to_the_ship_that_fired() {
+    let shot = Bullet::new(Position(4, 4), (1, 0), Team::RED, 1).with_shooter(7);
+    let entities = vec![ship(Team::BLUE, 4, 4), Entity::Bullet(shot)];
+
+    let resolved = resolve(&entities);
+
+    match &resolved[0] {
+        Entity::Ship(ship) => assert_eq!(
+            ship.destroyed_by(),
+            Some(Participant::Bullet {
+                team: Team::RED,
+                shooter: Some(7),
+            })
+        ),
+        _ => panic!("not a ship"),
+    }
+}
@@ -0,0 +1,74 @@
+//! Entities to set up test battlefields with, and ways to look inside them
+
+// not every test file uses every helper
+#![allow(dead_code)]
+
+use term_skirmish_rust::brain::Brains;
+use term_skirmish_rust::bullet::Bullet;
+use term_skirmish_rust::obstacle::Obstacle;
+use term_skirmish_rust::powerup::PowerUp;
+use term_skirmish_rust::spatial::SpatialIndex;
+use term_skirmish_rust::world::World;
+use term_skirmish_rust::{Arena, Effect, Entities, Entity, Position, Rules, Ship, Team};
+
+pub fn ship(team: Team, x: i32, y: i32) -> Entity {
+    Entity::Ship(Ship::at(team, Position(x, y)))
+}
+
+pub fn ship_with_hp(team: Team, x: i32, y: i32, hp: u16) -> Entity {
+    Entity::Ship(Ship::at(team, Position(x, y)).with_hp(hp, hp))
+}
+
+pub fn bullet(x: i32, y: i32) -> Entity {
+    aimed_bullet(Team::WHITE, x, y, (1, 0))
+}
+
+pub fn aimed_bullet(team: Team, x: i32, y: i32, direction: (i16, i16)) -> Entity {
+    Entity::Bullet(Bullet::new(Position(x, y), direction, team, 1))
+}
+
+pub fn obstacle(x: i32, y: i32) -> Entity {
+    Entity::Obstacle(Obstacle::new(Position(x, y)))
+}
+
+pub fn power_up(effect: Effect, x: i32, y: i32) -> Entity {
+    Entity::PowerUp(PowerUp::new(Position(x, y), effect))
+}
+
+pub fn hp(entity: &Entity) -> u16 {
+    match entity {
+        Entity::Ship(ship) => ship.hp(),
+        _ => panic!("not a ship"),
+    }
+}
+
+pub fn has_effect(entity: &Entity, effect: Effect) -> bool {
+    match entity {
+        Entity::Ship(ship) => ship.has_effect(effect),
+        _ => panic!("not a ship"),
+    }
+}
+
+pub fn explosion_health(entity: &Entity) -> u16 {
+    match entity {
+        Entity::Explosion(explosion) => explosion.health(),
+        _ => panic!("not an explosion"),
+    }
+}
+
+/// Run `f` looking at the battlefield, on an 80x24 arena
+pub fn in_world<R>(entities: &Entities, rules: &Rules, f: impl FnOnce(&World) -> R) -> R {
+    let index = SpatialIndex::new(entities);
+    let brains = Brains::default();
+
+    f(&World {
+        entities,
+        index: &index,
+        arena: Arena {
+            width: 80,
+            height: 24,
+        },
+        rules,
+        brains: &brains,
+    })
+}
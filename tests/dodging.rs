@@ -0,0 +1,137 @@
+mod common;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use term_skirmish_rust::dodge::{incoming_bullet, sidestep};
+use term_skirmish_rust::{Entities, Position, Rules, Team};
+
+use common::{aimed_bullet, in_world, obstacle, ship};
+
+const SHIP: Position = Position(10, 10);
+
+fn incoming(entities: &Entities, rules: &Rules) -> Option<(i16, i16)> {
+    in_world(entities, rules, |world| {
+        incoming_bullet(SHIP, Team::BLUE, world)
+    })
+}
+
+#[test]
+fn sees_a_bullet_coming_within_look_ahead() {
+    let rules = Rules::default();
+
+    let entities = vec![
+        ship(Team::BLUE, SHIP.0, SHIP.1),
+        aimed_bullet(Team::RED, SHIP.0, SHIP.1 - 4, (0, 1)),
+    ];
+    assert_eq!(incoming(&entities, &rules), Some((0, 1)));
+
+    let entities = vec![
+        ship(Team::BLUE, SHIP.0, SHIP.1),
+        aimed_bullet(Team::RED, SHIP.0 - 2, SHIP.1 + 2, (1, -1)),
+    ];
+    assert_eq!(incoming(&entities, &rules), Some((1, -1)));
+}
+
+#[test]
+fn overlooks_a_bullet_past_look_ahead() {
+    let entities = vec![
+        ship(Team::BLUE, SHIP.0, SHIP.1),
+        aimed_bullet(Team::RED, SHIP.0, SHIP.1 - 5, (0, 1)),
+    ];
+
+    assert_eq!(incoming(&entities, &Rules::default()), None);
+}
+
+#[test]
+fn overlooks_a_bullet_going_elsewhere() {
+    let entities = vec![
+        ship(Team::BLUE, SHIP.0, SHIP.1),
+        aimed_bullet(Team::RED, SHIP.0, SHIP.1 - 2, (1, 1)),
+        aimed_bullet(Team::RED, SHIP.0, SHIP.1 - 2, (0, -1)),
+    ];
+
+    assert_eq!(incoming(&entities, &Rules::default()), None);
+}
+
+#[test]
+fn overlooks_a_bullet_behind_an_obstacle() {
+    let entities = vec![
+        ship(Team::BLUE, SHIP.0, SHIP.1),
+        obstacle(SHIP.0, SHIP.1 - 2),
+        aimed_bullet(Team::RED, SHIP.0, SHIP.1 - 3, (0, 1)),
+    ];
+
+    assert_eq!(incoming(&entities, &Rules::default()), None);
+}
+
+#[test]
+fn overlooks_its_own_team_without_friendly_fire() {
+    let entities = vec![
+        ship(Team::BLUE, SHIP.0, SHIP.1),
+        aimed_bullet(Team::BLUE, SHIP.0, SHIP.1 - 2, (0, 1)),
+    ];
+
+    let rules = Rules {
+        friendly_fire: false,
+        ..Rules::default()
+    };
+    assert_eq!(incoming(&entities, &rules), None);
+
+    let rules = Rules {
+        friendly_fire: true,
+        ..Rules::default()
+    };
+    assert_eq!(incoming(&entities, &rules), Some((0, 1)));
+}
+
+/// A ship on the left edge with a bullet coming down on it.
+/// Of its four ways out, the two to the left are off the arena,
+/// so an obstacle to the right and a bullet crossing ahead decide what is left.
+fn edge_layout(blocked_right: bool, crossfire: bool) -> Entities {
+    let mut entities = vec![
+        ship(Team::BLUE, 1, 10),
+        aimed_bullet(Team::RED, 1, 8, (0, 1)),
+    ];
+
+    if blocked_right {
+        entities.push(obstacle(2, 10));
+    }
+    if crossfire {
+        // headed straight for the spot diagonally ahead
+        entities.push(aimed_bullet(Team::RED, 2, 13, (0, -1)));
+    }
+
+    entities
+}
+
+fn sidesteps(entities: &Entities) -> Vec<Option<(i16, i16)>> {
+    in_world(entities, &Rules::default(), |world| {
+        (0..20)
+            .map(|seed| {
+                let mut rng = StdRng::seed_from_u64(seed);
+                sidestep(Position(1, 10), Team::BLUE, (0, 1), &mut rng, world)
+            })
+            .collect()
+    })
+}
+
+#[test]
+fn sidesteps_at_right_angles_to_the_bullet() {
+    let steps = sidesteps(&edge_layout(false, false));
+
+    assert!(steps.iter().all(|&step| step == Some((1, 0))));
+}
+
+#[test]
+fn sidesteps_around_an_obstacle() {
+    let steps = sidesteps(&edge_layout(true, false));
+
+    assert!(steps.iter().all(|&step| step == Some((1, 1))));
+}
+
+#[test]
+fn stays_put_rather_than_sidestep_into_another_bullet() {
+    let steps = sidesteps(&edge_layout(true, true));
+
+    assert!(steps.iter().all(|&step| step.is_none()));
+}
@@ -0,0 +1,78 @@
+use rand::rngs::StdRng;
+use term_skirmish_rust::world::World;
+use term_skirmish_rust::{
+    Arena, Entity, EntityBehavior, Formation, Game, GameConfig, Intent, Position, Rules, Ship,
+    ShipBrain, Team,
+};
+
+const MIDDLE: Position = Position(30, 12);
+
+/// Heads for a spot on the bottom edge, then for the middle once it gets there
+struct EdgeThenMiddle;
+
+impl ShipBrain for EdgeThenMiddle {
+    fn think(&self, ship: &Ship, _: &mut StdRng, world: &World) -> Intent {
+        let bottom = i32::from(world.arena.height);
+
+        let destination = if !ship.has_reached_destination() {
+            ship.destination()
+        } else if ship.get_position().1 >= bottom {
+            MIDDLE
+        } else {
+            Position(10, bottom)
+        };
+
+        Intent {
+            destination,
+            fire: None,
+        }
+    }
+}
+
+#[test]
+fn leader_heading_for_the_edge_keeps_going() {
+    for formation in [Formation::Line, Formation::Wedge, Formation::Swarm] {
+        let mut rules = Rules {
+            reinforcement_odds: u32::MAX,
+            ..Rules::default()
+        };
+        rules.formations.insert(Team::BLUE, formation);
+
+        let mut game = Game::new(GameConfig {
+            teams: 1,
+            wave: 3,
+            seed: Some(1),
+            arena: Arena {
+                width: 60,
+                height: 24,
+            },
+            rules,
+            ..Default::default()
+        })
+        .unwrap();
+        game.set_brain(Team::BLUE, EdgeThenMiddle);
+
+        let leader_at = |game: &Game| {
+            game.entities().iter().find_map(|entity| match entity {
+                Entity::Ship(ship) if ship.id() == 0 => Some(ship.get_position()),
+                _ => None,
+            })
+        };
+
+        let mut visited = Vec::new();
+
+        for _ in 0..5000 {
+            game.tick();
+            visited.extend(leader_at(&game));
+        }
+
+        let edge = visited.iter().position(|pos| pos.1 == 24);
+        let middle = visited.iter().rposition(|&pos| pos == MIDDLE);
+
+        assert!(
+            matches!((edge, middle), (Some(edge), Some(middle)) if edge < middle),
+            "{} formation never made it from the edge to the middle",
+            formation
+        );
+    }
+}
@@ -0,0 +1,34 @@
+use term_skirmish_rust::stats::Stats;
+use term_skirmish_rust::{Event, Participant, Position, Team, TickReport};
+
+/// `id` of `team` takes out a ship of the other team
+fn kill(tick: u64, team: Team, id: u32, victim: (Team, u32)) -> TickReport {
+    TickReport {
+        tick,
+        events: vec![Event::ShipDestroyed {
+            id: victim.1,
+            team: victim.0,
+            position: Position(1, 1),
+            by: Some(Participant::Ship { team, id }),
+        }],
+    }
+}
+
+#[test]
+fn top_gun_tie_goes_to_the_ship_that_got_there_first() {
+    let mut stats = Stats::default();
+
+    stats.record(&kill(1, Team::BLUE, 7, (Team::RED, 1)));
+    stats.record(&kill(2, Team::RED, 2, (Team::BLUE, 3)));
+    assert_eq!(
+        stats.top_gun().map(|(team, t)| (team, t.ship)),
+        Some((Team::BLUE, 7))
+    );
+
+    stats.record(&kill(3, Team::RED, 2, (Team::BLUE, 4)));
+    stats.record(&kill(4, Team::BLUE, 7, (Team::RED, 5)));
+    assert_eq!(
+        stats.top_gun().map(|(team, t)| (team, t.ship)),
+        Some((Team::RED, 2))
+    );
+}
@@ -0,0 +1,173 @@
+mod common;
+
+use std::collections::HashSet;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use term_skirmish_rust::brain::aim;
+use term_skirmish_rust::world::World;
+use term_skirmish_rust::{
+    Entities, Entity, EntityBehavior, Position, Rules, Ship, Targeting, Team,
+};
+
+use common::{in_world, ship};
+
+/// Run `f` with the first entity, which must be a ship, looking at the battlefield
+fn with_world<R>(entities: &Entities, f: impl FnOnce(&Ship, &World) -> R) -> R {
+    in_world(entities, &Rules::default(), |world| match &entities[0] {
+        Entity::Ship(ship) => f(ship, world),
+        _ => panic!("not a ship"),
+    })
+}
+
+fn pick(entities: &Entities, targeting: Targeting, seed: u64) -> Option<Position> {
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    with_world(entities, |ship, world| {
+        targeting.pick(ship, &mut rng, world)
+    })
+    .map(|i| entities[i].get_position())
+}
+
+#[test]
+fn nearest_goes_after_the_closest_enemy() {
+    let entities = vec![
+        ship(Team::BLUE, 10, 10),
+        ship(Team::RED, 20, 10),
+        ship(Team::RED, 12, 14),
+        ship(Team::RED, 30, 20),
+    ];
+
+    assert_eq!(
+        pick(&entities, Targeting::Nearest, 0),
+        Some(Position(12, 14))
+    );
+}
+
+#[test]
+fn nearest_breaks_ties_by_entity_order() {
+    let entities = vec![
+        ship(Team::BLUE, 10, 10),
+        ship(Team::RED, 13, 10),
+        ship(Team::RED, 7, 10),
+    ];
+
+    assert_eq!(
+        pick(&entities, Targeting::Nearest, 0),
+        Some(Position(13, 10))
+    );
+}
+
+#[test]
+fn nearest_looks_outward_through_a_crowd() {
+    let mut entities = vec![ship(Team::BLUE, 10, 10)];
+    entities.extend((30..60).map(|x| ship(Team::RED, x, 20)));
+    entities.push(ship(Team::GREEN, 12, 12));
+    entities.push(ship(Team::RED, 8, 8));
+
+    assert_eq!(
+        pick(&entities, Targeting::Nearest, 0),
+        Some(Position(12, 12))
+    );
+}
+
+#[test]
+fn weakest_cluster_picks_off_the_straggler() {
+    let entities = vec![
+        ship(Team::BLUE, 10, 10),
+        ship(Team::RED, 12, 10),
+        ship(Team::RED, 13, 10),
+        ship(Team::RED, 12, 11),
+        ship(Team::RED, 30, 20),
+    ];
+
+    assert_eq!(
+        pick(&entities, Targeting::WeakestCluster, 0),
+        Some(Position(30, 20))
+    );
+}
+
+#[test]
+fn near_friends_helps_out_a_teammate() {
+    let entities = vec![
+        ship(Team::BLUE, 10, 10),
+        ship(Team::BLUE, 40, 20),
+        ship(Team::RED, 12, 10),
+        ship(Team::RED, 38, 20),
+    ];
+
+    assert_eq!(
+        pick(&entities, Targeting::NearFriends, 0),
+        Some(Position(38, 20))
+    );
+}
+
+#[test]
+fn random_picks_any_enemy() {
+    let entities = vec![
+        ship(Team::BLUE, 10, 10),
+        ship(Team::BLUE, 11, 10),
+        ship(Team::RED, 20, 10),
+        ship(Team::YELLOW, 12, 14),
+        ship(Team::RED, 30, 20),
+    ];
+
+    let picked: HashSet<_> = (0..50)
+        .map(|seed| pick(&entities, Targeting::Random, seed).unwrap())
+        .collect();
+
+    let enemies: HashSet<_> = [Position(20, 10), Position(12, 14), Position(30, 20)].into();
+
+    assert_eq!(picked, enemies);
+}
+
+#[test]
+fn no_target_without_enemies() {
+    let entities = vec![ship(Team::BLUE, 10, 10), ship(Team::BLUE, 20, 10)];
+
+    for targeting in [
+        Targeting::Nearest,
+        Targeting::WeakestCluster,
+        Targeting::NearFriends,
+        Targeting::Random,
+    ] {
+        assert_eq!(pick(&entities, targeting, 0), None);
+    }
+}
+
+#[test]
+fn aim_prefers_the_nearest_enemy_in_line() {
+    let entities = vec![
+        ship(Team::BLUE, 10, 10),
+        ship(Team::RED, 20, 10),
+        ship(Team::RED, 11, 12),
+        ship(Team::RED, 10, 13),
+        ship(Team::BLUE, 9, 10),
+    ];
+
+    assert_eq!(with_world(&entities, aim), Some((0, 1)));
+}
+
+#[test]
+fn aim_does_not_shoot_through_teammates() {
+    let entities = vec![
+        ship(Team::BLUE, 10, 10),
+        ship(Team::RED, 10, 13),
+        ship(Team::BLUE, 10, 11),
+        ship(Team::RED, 20, 10),
+    ];
+
+    assert_eq!(with_world(&entities, aim), Some((1, 0)));
+}
+
+#[test]
+fn aim_does_not_shoot_through_obstacles() {
+    let entities = vec![
+        ship(Team::BLUE, 10, 10),
+        ship(Team::RED, 10, 13),
+        Entity::obstacle(Position(10, 12)),
+        ship(Team::RED, 15, 15),
+    ];
+
+    assert_eq!(with_world(&entities, aim), Some((1, 1)));
+}